@@ -41,8 +41,39 @@ fn main() {
     std::process::exit(match r {
         Ok(()) => 0,
         Err(err) => {
-            eprintln!("ERROR: {}", err);
+            eprintln!("ERROR: {}", error_chain(err.as_ref()));
             1
         }
     });
 }
+
+// Format an error and each of its sources, outermost first,
+// as `error: source: source ...`.
+fn error_chain(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rsgit_core::repo;
+
+    #[test]
+    fn error_chain_fn() {
+        let err = repo::Error::from(io::Error::new(io::ErrorKind::NotFound, "no such thing"));
+        assert_eq!(error_chain(&err), "I/O error: no such thing");
+
+        let err = repo::Error::InvalidRef("HEAD".to_string());
+        assert_eq!(error_chain(&err), "ref `HEAD` is malformed");
+    }
+}
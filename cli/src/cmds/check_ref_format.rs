@@ -0,0 +1,171 @@
+use std::io::Write;
+
+use crate::{App, Result};
+
+use clap::{self, Arg, ArgMatches, Error, ErrorKind, SubCommand};
+
+use rsgit_core::refs::{normalize_ref_name, validate_ref_name};
+
+pub(crate) fn subcommand<'a, 'b>() -> clap::App<'a, 'b> {
+    SubCommand::with_name("check-ref-format")
+        .about("Ensures that a reference name is well formed")
+        .arg(
+            Arg::with_name("allow-onelevel")
+                .long("allow-onelevel")
+                .help("Allow ref names with only one component (e.g. 'foo')"),
+        )
+        .arg(
+            Arg::with_name("normalize")
+                .long("normalize")
+                .help("Collapse adjacent '/' characters and print the normalized name"),
+        )
+        .arg(
+            Arg::with_name("branch")
+                .long("branch")
+                .conflicts_with_all(&["allow-onelevel", "normalize"])
+                .help("Check that the name is a valid branch name and print it"),
+        )
+        .arg(Arg::with_name("refname").required(true))
+}
+
+pub(crate) fn run(app: &mut App, args: &ArgMatches) -> Result<()> {
+    let refname = args.value_of("refname").unwrap();
+
+    if args.is_present("branch") {
+        return check_branch(app, refname);
+    }
+
+    let normalized;
+    let name = if args.is_present("normalize") {
+        normalized = normalize_ref_name(refname.as_bytes());
+        normalized.as_slice()
+    } else {
+        refname.as_bytes()
+    };
+
+    if validate_ref_name(name, args.is_present("allow-onelevel")).is_err() {
+        return Err(invalid_name_error(refname, "ref"));
+    }
+
+    if args.is_present("normalize") {
+        app.write_all(name)?;
+        writeln!(app)?;
+    }
+
+    Ok(())
+}
+
+fn check_branch(app: &mut App, name: &str) -> Result<()> {
+    // Command-line git also expands `@{-N}` to the Nth previously
+    // checked-out branch here. rsgit doesn't track that history yet.
+
+    if name.starts_with('-') || name == "HEAD" {
+        return Err(invalid_name_error(name, "branch"));
+    }
+
+    let full_name = format!("refs/heads/{}", name);
+    if validate_ref_name(full_name.as_bytes(), false).is_err() {
+        return Err(invalid_name_error(name, "branch"));
+    }
+
+    writeln!(app, "{}", name)?;
+    Ok(())
+}
+
+fn invalid_name_error(name: &str, what: &str) -> Box<Error> {
+    Box::new(Error {
+        message: format!("'{}' is not a valid {} name", name, what),
+        kind: ErrorKind::InvalidValue,
+        info: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::App;
+
+    #[test]
+    fn valid_ref_name() {
+        let stdout = App::run_with_args(vec!["check-ref-format", "refs/heads/x"]).unwrap();
+        assert!(stdout.is_empty());
+    }
+
+    #[test]
+    fn invalid_ref_name() {
+        let err = App::run_with_args(vec!["check-ref-format", "refs/heads/x..y"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'refs/heads/x..y' is not a valid ref name\n"
+        );
+    }
+
+    #[test]
+    fn allow_onelevel() {
+        let err = App::run_with_args(vec!["check-ref-format", "foo"]).unwrap_err();
+        assert_eq!(err.to_string(), "'foo' is not a valid ref name\n");
+
+        let stdout =
+            App::run_with_args(vec!["check-ref-format", "--allow-onelevel", "foo"]).unwrap();
+        assert!(stdout.is_empty());
+    }
+
+    #[test]
+    fn normalize() {
+        let stdout =
+            App::run_with_args(vec!["check-ref-format", "--normalize", "//heads//foo"]).unwrap();
+        assert_eq!(stdout, b"heads/foo\n");
+
+        let err = App::run_with_args(vec!["check-ref-format", "--normalize", "/foo"]).unwrap_err();
+        assert_eq!(err.to_string(), "'/foo' is not a valid ref name\n");
+
+        let stdout = App::run_with_args(vec![
+            "check-ref-format",
+            "--normalize",
+            "--allow-onelevel",
+            "/foo",
+        ])
+        .unwrap();
+        assert_eq!(stdout, b"foo\n");
+    }
+
+    #[test]
+    fn branch() {
+        let stdout = App::run_with_args(vec!["check-ref-format", "--branch", "foo"]).unwrap();
+        assert_eq!(stdout, b"foo\n");
+
+        let err = App::run_with_args(vec!["check-ref-format", "--branch", "a..b"]).unwrap_err();
+        assert_eq!(err.to_string(), "'a..b' is not a valid branch name\n");
+
+        let err = App::run_with_args(vec!["check-ref-format", "--branch", "HEAD"]).unwrap_err();
+        assert_eq!(err.to_string(), "'HEAD' is not a valid branch name\n");
+
+        let err =
+            App::run_with_args(vec!["check-ref-format", "--branch", "--", "-foo"]).unwrap_err();
+        assert_eq!(err.to_string(), "'-foo' is not a valid branch name\n");
+    }
+
+    #[test]
+    fn error_branch_with_normalize() {
+        let err = App::run_with_args(vec!["check-ref-format", "--branch", "--normalize", "foo"])
+            .unwrap_err();
+
+        let errmsg = err.to_string();
+        assert!(
+            errmsg.contains("cannot be used with"),
+            "\nincorrect error message:\n\n{}",
+            errmsg
+        );
+    }
+
+    #[test]
+    fn error_no_refname() {
+        let err = App::run_with_args(vec!["check-ref-format"]).unwrap_err();
+
+        let errmsg = err.to_string();
+        assert!(
+            errmsg.contains("required arguments were not provided"),
+            "\nincorrect error message:\n\n{}",
+            errmsg
+        );
+    }
+}
@@ -1,14 +1,16 @@
-use std::io::Write;
+use std::io::{self, Read, Write};
 
 use crate::{find_repo, App, Result};
 
 use clap::{self, Arg, ArgMatches, Error, ErrorKind, SubCommand};
 
 use rsgit_core::{
-    object::{ContentSource, FileContentSource, Kind, Object, ReadContentSource},
+    object::{ContentSource, ContentSourceOpenResult, FileContentSource, Kind, Object},
     repo::Repo,
 };
 
+use tempfile::NamedTempFile;
+
 pub(crate) fn subcommand<'a, 'b>() -> clap::App<'a, 'b> {
     SubCommand::with_name("hash-object")
         .about("Compute object ID and optionally creates a blob from a file")
@@ -49,7 +51,7 @@ pub(crate) fn run(app: &mut App, args: &ArgMatches) -> Result<()> {
 
     if args.is_present("w") {
         let mut repo = find_repo::from_current_dir()?;
-        repo.put_loose_object(&object)?;
+        repo.write_object(&object)?;
     }
 
     writeln!(app, "{}", object.id())?;
@@ -57,7 +59,7 @@ pub(crate) fn run(app: &mut App, args: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
-fn object_from_args(app: &mut App, args: &ArgMatches) -> Result<Object> {
+fn object_from_args(app: &mut App, args: &ArgMatches) -> Result<Object<'static>> {
     let kind = type_from_args(&args)?;
     let content_source = content_source_from_args(app, &args)?;
     let object = Object::new(&kind, content_source)?;
@@ -94,7 +96,7 @@ fn content_source_from_args(app: &mut App, args: &ArgMatches) -> Result<Box<dyn
     if file.is_some() && !stdin {
         Ok(Box::new(FileContentSource::new(file.unwrap())?))
     } else if stdin && file.is_none() {
-        Ok(Box::new(ReadContentSource::new(&mut app.stdin)?))
+        stdin_content_source(&mut app.stdin)
     } else {
         Err(Box::new(Error {
             message: "content source must be either --stdin or a file path".to_string(),
@@ -104,6 +106,49 @@ fn content_source_from_args(app: &mut App, args: &ArgMatches) -> Result<Box<dyn
     }
 }
 
+// The content is read twice (once to compute the ID and again to write
+// the object), so stdin must be kept somewhere. Small inputs are kept in
+// memory; anything larger than this is copied to a temporary file.
+const STDIN_SPILL_THRESHOLD: u64 = 1024 * 1024;
+
+fn stdin_content_source(stdin: &mut dyn Read) -> Result<Box<dyn ContentSource>> {
+    let mut content = Vec::new();
+    (&mut *stdin)
+        .take(STDIN_SPILL_THRESHOLD + 1)
+        .read_to_end(&mut content)?;
+
+    if content.len() as u64 <= STDIN_SPILL_THRESHOLD {
+        return Ok(Box::new(content));
+    }
+
+    let mut file = NamedTempFile::new()?;
+    file.write_all(&content)?;
+    io::copy(stdin, &mut file)?;
+    file.flush()?;
+
+    let source = FileContentSource::new(file.path())?;
+    Ok(Box::new(TempFileContentSource {
+        _file: file,
+        source,
+    }))
+}
+
+// Reads content from a temporary file, which is deleted when this is dropped.
+struct TempFileContentSource {
+    _file: NamedTempFile,
+    source: FileContentSource,
+}
+
+impl ContentSource for TempFileContentSource {
+    fn len(&self) -> u64 {
+        self.source.len()
+    }
+
+    fn open(&self) -> ContentSourceOpenResult {
+        self.source.open()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -114,7 +159,7 @@ mod tests {
 
     use crate::{temp_cwd::TempCwd, App};
 
-    use rsgit_on_disk::TempGitRepo;
+    use rsgit_on_disk::{test_support::assert_same_objects, OnDiskRepo, TempGitRepo};
     use serial_test::serial;
     use tempfile::TempDir;
 
@@ -186,7 +231,68 @@ mod tests {
 
         assert_eq!(c_stdout, r_stdout);
 
-        assert!(!dir_diff::is_different(c_path, r_path).unwrap());
+        assert_same_objects(
+            &OnDiskRepo::open(c_path).unwrap(),
+            &OnDiskRepo::open(r_path).unwrap(),
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn large_stdin_matches_command_line_git() {
+        // Large enough to be spilled to a temporary file, and larger
+        // than the in-memory limit of ReadContentSource.
+        let stdin: Vec<u8> = (0..21 * 1024 * 1024u32).map(|n| (n % 251) as u8).collect();
+
+        let c_tgr = TempGitRepo::new();
+        let c_path = c_tgr.path();
+
+        let mut cgit = Command::new("git")
+            .current_dir(c_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .args(&["hash-object", "-w", "--stdin"])
+            .spawn()
+            .unwrap();
+
+        {
+            let cgit_stdin = cgit.stdin.as_mut().unwrap();
+            cgit_stdin.write_all(&stdin).unwrap();
+        }
+
+        let c_stdout = cgit.wait_with_output().unwrap().stdout;
+        let r_tgr = TempGitRepo::new();
+        let r_path = r_tgr.path();
+
+        let _r_cwd = TempCwd::new(r_path);
+        let r_stdout =
+            App::run_with_stdin_and_args(stdin, vec!["hash-object", "-w", "--stdin"]).unwrap();
+
+        assert_eq!(c_stdout, r_stdout);
+
+        assert_same_objects(
+            &OnDiskRepo::open(c_path).unwrap(),
+            &OnDiskRepo::open(r_path).unwrap(),
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn write_existing_object() {
+        let r_tgr = TempGitRepo::new();
+        let _r_cwd = TempCwd::new(r_tgr.path());
+
+        let stdin: Vec<u8> = b"test content\n".to_vec();
+        let expected_stdout = "d670460b4b4aece5915caf5c68d12f560a9fe3e4\n";
+
+        let stdout =
+            App::run_with_stdin_and_args(stdin.clone(), vec!["hash-object", "-w", "--stdin"])
+                .unwrap();
+        assert_eq!(stdout, expected_stdout.as_bytes());
+
+        let stdout =
+            App::run_with_stdin_and_args(stdin, vec!["hash-object", "-w", "--stdin"]).unwrap();
+        assert_eq!(stdout, expected_stdout.as_bytes());
     }
 
     #[test]
@@ -237,7 +343,10 @@ mod tests {
 
         assert_eq!(c_stdout, r_stdout);
 
-        assert!(!dir_diff::is_different(c_path, r_path).unwrap());
+        assert_same_objects(
+            &OnDiskRepo::open(c_path).unwrap(),
+            &OnDiskRepo::open(r_path).unwrap(),
+        );
     }
 
     #[test]
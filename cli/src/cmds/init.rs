@@ -1,6 +1,6 @@
-use std::{io::Write, path::Path};
+use std::{fs, io::Write, path::Path};
 
-use crate::{App, Result};
+use crate::{find_repo, App, Result};
 
 use clap::{self, Arg, ArgMatches, SubCommand};
 use rsgit_on_disk::OnDiskRepo;
@@ -8,6 +8,12 @@ use rsgit_on_disk::OnDiskRepo;
 pub(crate) fn subcommand<'a, 'b>() -> clap::App<'a, 'b> {
     SubCommand::with_name("init")
         .about("Create an empty git repository")
+        .arg(
+            Arg::with_name("template")
+                .long("template")
+                .value_name("template-directory")
+                .help("Copy files from this directory into the new .git directory"),
+        )
         .arg(
             Arg::with_name("directory")
                 .required(true)
@@ -19,7 +25,17 @@ pub(crate) fn run(app: &mut App, init_matches: &ArgMatches) -> Result<()> {
     let dir = init_matches.value_of("directory").unwrap();
 
     let path = Path::new(dir);
-    OnDiskRepo::init(path)?;
+    match init_matches.value_of("template") {
+        Some(template) => OnDiskRepo::init_with_template(path, template)?,
+        None => OnDiskRepo::init(path)?,
+    };
+
+    // Like command-line git, also create the object store named by
+    // `GIT_OBJECT_DIRECTORY` (if any) so that later commands can use it.
+    if let Some(objects_dir) = find_repo::objects_dir_from_env() {
+        fs::create_dir_all(objects_dir.join("info"))?;
+        fs::create_dir_all(objects_dir.join("pack"))?;
+    }
 
     writeln!(
         app,
@@ -32,10 +48,14 @@ pub(crate) fn run(app: &mut App, init_matches: &ArgMatches) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use std::{env, fs};
+
     use crate::App;
 
     use rsgit_on_disk::TempGitRepo;
 
+    use serial_test::serial;
+
     #[test]
     fn matches_command_line_git() {
         let tgr = TempGitRepo::new();
@@ -52,6 +72,67 @@ mod tests {
         assert!(!dir_diff::is_different(c_path, r_path.path()).unwrap());
     }
 
+    #[test]
+    fn template() {
+        let template_dir = tempfile::tempdir().unwrap();
+        let hooks_dir = template_dir.path().join("hooks");
+        fs::create_dir_all(&hooks_dir).unwrap();
+        fs::write(hooks_dir.join("pre-commit"), "#!/bin/sh\nexit 0\n").unwrap();
+
+        let r_path = tempfile::tempdir().unwrap();
+        let r_pathstr = r_path.path().to_str().unwrap();
+
+        let stdout = App::run_with_args(vec![
+            "init",
+            "--template",
+            template_dir.path().to_str().unwrap(),
+            &r_pathstr,
+        ])
+        .unwrap();
+
+        let expected_std = format!("Initialized empty Git repository in {}\n", r_pathstr);
+        assert_eq!(stdout, expected_std.as_bytes());
+
+        assert!(r_path.path().join(".git/hooks/pre-commit").is_file());
+    }
+
+    #[test]
+    #[serial]
+    fn objects_dir_from_env_var() {
+        let r_path = tempfile::tempdir().unwrap();
+        let objects_temp = tempfile::tempdir().unwrap();
+        let objects_dir = objects_temp.path().join("objects");
+
+        env::set_var("GIT_OBJECT_DIRECTORY", &objects_dir);
+        let result = App::run_with_args(vec!["init", r_path.path().to_str().unwrap()]);
+        env::remove_var("GIT_OBJECT_DIRECTORY");
+
+        result.unwrap();
+        assert!(objects_dir.join("info").is_dir());
+        assert!(objects_dir.join("pack").is_dir());
+    }
+
+    #[test]
+    fn error_no_template_dir() {
+        let r_path = tempfile::tempdir().unwrap();
+        let template_path = r_path.path().join("no-such-template");
+
+        let err = App::run_with_args(vec![
+            "init",
+            "--template",
+            template_path.to_str().unwrap(),
+            r_path.path().to_str().unwrap(),
+        ])
+        .unwrap_err();
+
+        let errmsg = err.to_string();
+        assert!(
+            errmsg.contains("template_dir doesn't exist"),
+            "\nincorrect error message:\n\n{}",
+            errmsg
+        );
+    }
+
     #[test]
     fn error_no_dir() {
         let err = App::run_with_args(vec!["init"]).unwrap_err();
@@ -1,10 +1,12 @@
 use crate::{App, Result};
 
+mod check_ref_format;
 mod hash_object;
 mod init;
 
 pub(crate) fn add_subcommands<'a, 'b>(app: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
-    app.subcommand(hash_object::subcommand())
+    app.subcommand(check_ref_format::subcommand())
+        .subcommand(hash_object::subcommand())
         .subcommand(init::subcommand())
 }
 
@@ -14,6 +16,7 @@ pub(crate) fn dispatch(app: &mut App) -> Result<()> {
     // the App struct through to subcommand imps.
 
     match matches.subcommand() {
+        ("check-ref-format", Some(m)) => check_ref_format::run(app, m),
         ("hash-object", Some(m)) => hash_object::run(app, &m),
         ("init", Some(m)) => init::run(app, &m),
         _ => unreachable!(),
@@ -1,4 +1,7 @@
-use std::{env, path::Path};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
 use rsgit_core::repo::Result;
 use rsgit_on_disk::OnDiskRepo;
@@ -14,12 +17,30 @@ use rsgit_on_disk::OnDiskRepo;
 // where there is a `.git` directory nested within the
 // given path.
 //
+// If `GIT_OBJECT_DIRECTORY` is set, the repo's objects are read
+// from and written to that directory instead.
+//
 // Returns a `Result` with `rsgit_core::repo::OnDiskRepo` or
 // `rsgit_core::repo::Error` if no such repo exists.
 pub fn from_path<P: AsRef<Path>>(path: P) -> Result<OnDiskRepo> {
     // TO DO: Look in other places for repo.
     // https://github.com/rust-git/rsgit/issues/80
-    OnDiskRepo::new(path)
+    let mut repo = OnDiskRepo::new(path)?;
+
+    if let Some(objects_dir) = objects_dir_from_env() {
+        repo.set_objects_dir(objects_dir);
+    }
+
+    Ok(repo)
+}
+
+// Returns the object store location named by `GIT_OBJECT_DIRECTORY`,
+// if that environment variable is set and not empty.
+pub fn objects_dir_from_env() -> Option<PathBuf> {
+    match env::var_os("GIT_OBJECT_DIRECTORY") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => None,
+    }
 }
 
 // Discover a git repo starting from the current working directory.
@@ -52,12 +73,29 @@ mod tests {
 
     use rsgit_on_disk::TempGitRepo;
 
+    use serial_test::serial;
+
     #[test]
+    #[serial]
     fn simple_case() {
         let tgr = TempGitRepo::new();
         let path = tgr.path();
         let repo = from_path(path).unwrap();
-        assert_eq!(repo.work_dir(), path);
+        assert_eq!(repo.work_dir().unwrap(), path);
+        assert_eq!(repo.objects_dir(), path.join(".git/objects"));
+    }
+
+    #[test]
+    #[serial]
+    fn objects_dir_from_env_var() {
+        let tgr = TempGitRepo::new();
+        let objects_temp = tempfile::tempdir().unwrap();
+
+        env::set_var("GIT_OBJECT_DIRECTORY", objects_temp.path());
+        let repo = from_path(tgr.path());
+        env::remove_var("GIT_OBJECT_DIRECTORY");
+
+        assert_eq!(repo.unwrap().objects_dir(), objects_temp.path());
     }
 
     #[test]
@@ -0,0 +1,91 @@
+use std::{ffi::OsStr, process::Command};
+
+use assert_cmd::cargo;
+
+// C git's t1402 runs `check-ref-format` against a long table of names
+// without needing a repository. We do the same, comparing exit status
+// and output with C git for each case.
+
+fn compare_git_and_rsgit(args: &[&str]) {
+    let cgit = Command::new("git")
+        .arg("check-ref-format")
+        .args(args)
+        .output()
+        .unwrap();
+
+    let rsgit = cargo::cargo_bin("rsgit");
+    let rsgit = Command::new(OsStr::new(&rsgit))
+        .arg("check-ref-format")
+        .args(args)
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        cgit.status.success(),
+        rsgit.status.success(),
+        "exit status differs for {:?}",
+        args
+    );
+    assert_eq!(cgit.stdout, rsgit.stdout, "stdout differs for {:?}", args);
+}
+
+const REF_NAMES: [&str; 31] = [
+    "",
+    "/",
+    "foo",
+    "foo/bar",
+    "foo/bar/baz",
+    "refs///heads/foo",
+    "heads/foo/",
+    "/heads/foo",
+    "///heads/foo",
+    "./foo",
+    "./foo/bar",
+    "foo/./bar",
+    "foo/bar/.",
+    ".refs/foo",
+    "refs/heads/foo.",
+    "heads/foo..bar",
+    "heads/foo?bar",
+    "foo./bar",
+    "heads/foo.lock",
+    "heads///foo.lock",
+    "foo.lock/bar",
+    "foo.lock///bar",
+    "heads/foo@bar",
+    "heads/v@{ation",
+    "heads/foo\\bar",
+    "heads/foo\tbar",
+    "heads/foo\x7fbar",
+    "heads/fu\u{00DF}",
+    "@",
+    "refs/heads/@",
+    "heads/foo~bar",
+];
+
+#[test]
+fn ref_names() {
+    for name in REF_NAMES.iter() {
+        compare_git_and_rsgit(&[name]);
+        compare_git_and_rsgit(&["--allow-onelevel", name]);
+        compare_git_and_rsgit(&["--normalize", name]);
+        compare_git_and_rsgit(&["--normalize", "--allow-onelevel", name]);
+    }
+}
+
+#[test]
+fn branch_names() {
+    for name in [
+        "foo",
+        "foo/bar",
+        "a..b",
+        "HEAD",
+        "refs/heads/x",
+        "-foo",
+        "@",
+    ]
+    .iter()
+    {
+        compare_git_and_rsgit(&["--branch", name]);
+    }
+}
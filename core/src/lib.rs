@@ -4,4 +4,5 @@
 
 pub mod object;
 pub mod path;
+pub mod refs;
 pub mod repo;
@@ -1,10 +1,12 @@
 use std::{
-    fmt,
+    cmp::Ordering,
+    env, fmt,
     str::{self, FromStr},
     string::String,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::object::parse_utils::split_once;
+use crate::object::{date, parse_utils::split_once};
 
 /// An `Attribution` combines a person's identity (name and e-mail address)
 /// with the timestamp for a particular action.
@@ -58,7 +60,7 @@ impl Attribution {
 
         let line = drop_last_space(line);
         let (tz_offset, line) = last_word(line);
-        let tz_offset = match tz_from_str(tz_offset.as_str()) {
+        let tz_offset = match date::parse_tz(tz_offset.as_str(), false) {
             Some(t) => t,
             _ => 0,
         };
@@ -77,6 +79,77 @@ impl Attribution {
         })
     }
 
+    /// Build an attribution from command-line git's environment variables
+    /// for `role`. For example, `"author"` reads `GIT_AUTHOR_NAME`,
+    /// `GIT_AUTHOR_EMAIL`, and `GIT_AUTHOR_DATE`.
+    ///
+    /// If the name or email variable isn't set, `default_name` or
+    /// `default_email` (typically `user.name` and `user.email` from the
+    /// repo's config) is used instead. If the date variable isn't set,
+    /// the current time is used with a `+0000` time zone.
+    ///
+    /// The date may be in any of the formats accepted by [`parse_date`].
+    ///
+    /// [`parse_date`]: #method.parse_date
+    ///
+    /// Returns `None` if no name or email is available or if the date
+    /// can't be parsed.
+    pub fn from_env(
+        role: &str,
+        default_name: Option<&str>,
+        default_email: Option<&str>,
+    ) -> Option<Attribution> {
+        let role = role.to_ascii_uppercase();
+        let var = |field: &str| env::var(format!("GIT_{}_{}", role, field)).ok();
+
+        let name = var("NAME").or_else(|| default_name.map(|n| n.to_string()))?;
+        let email = var("EMAIL").or_else(|| default_email.map(|e| e.to_string()))?;
+
+        let (timestamp, tz_offset) = match var("DATE") {
+            Some(date) => date::parse_date(&date)?,
+            None => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+                (now.as_secs() as i64, 0)
+            }
+        };
+
+        Some(Attribution {
+            name,
+            email,
+            timestamp,
+            tz_offset,
+        })
+    }
+
+    /// Parse a date in one of the formats that command-line git accepts
+    /// for `GIT_AUTHOR_DATE` and `GIT_COMMITTER_DATE`, returning the Unix
+    /// timestamp and the time zone offset (in minutes).
+    ///
+    /// The following [date formats] are supported:
+    ///
+    /// * git's raw format: `1112911993 -0700` or `@1112911993 -0700`
+    /// * ISO 8601: `2005-04-07T22:13:13+02:00`, `2005-04-07 22:13:13 +0200`,
+    ///   or `2005-04-07T20:13:13Z`
+    /// * RFC 2822: `Thu, 07 Apr 2005 22:13:13 +0200`
+    ///
+    /// Returns `None` if the date can't be parsed, if it has no time zone,
+    /// or if the time zone is out of range.
+    ///
+    /// [date formats]: https://git-scm.com/docs/git-commit#_date_formats
+    pub fn parse_date(s: &str) -> Option<(i64, i16)> {
+        date::parse_date(s)
+    }
+
+    // Returns a copy of this attribution with a different name and email.
+    pub(crate) fn with_identity(&self, name: &str, email: &str) -> Attribution {
+        Attribution {
+            name: name.to_string(),
+            email: email.to_string(),
+            timestamp: self.timestamp,
+            tz_offset: self.tz_offset,
+        }
+    }
+
     /// Returns the person's human-readable name.
     pub fn name(&self) -> &str {
         &self.name
@@ -107,6 +180,18 @@ impl Attribution {
         self.tz_offset
     }
 
+    /// Compares two attributions chronologically: by timestamp and then,
+    /// for equal timestamps, by time zone offset.
+    ///
+    /// `Attribution` doesn't implement `Ord` because equality also
+    /// considers name and email. Use this instead to sort by time, e.g.
+    /// `attributions.sort_by(Attribution::cmp_by_time)`.
+    pub fn cmp_by_time(&self, other: &Attribution) -> Ordering {
+        self.timestamp
+            .cmp(&other.timestamp)
+            .then(self.tz_offset.cmp(&other.tz_offset))
+    }
+
     /// Returns the timezone formatted in human readable offset from GMT.
     pub fn format_tz(&self) -> String {
         let sign = if self.tz_offset < 0 { "-" } else { "+" };
@@ -157,34 +242,6 @@ fn rsplit_once<'a>(s: &'a [u8], c: &u8) -> (&'a [u8], &'a [u8]) {
     }
 }
 
-fn tz_from_str(s: &str) -> Option<i16> {
-    let s = s.as_bytes();
-
-    if s.len() != 5 {
-        return None;
-    }
-
-    let sign: i16 = match &s[0..1] {
-        b"+" => 1,
-        b"-" => -1,
-        _ => {
-            return None;
-        }
-    };
-
-    let hh = from_digit(s[1]) * 10 + from_digit(s[2]);
-    let mm = from_digit(s[3]) * 10 + from_digit(s[3]);
-    Some(sign * (hh * 60 + mm))
-}
-
-fn from_digit(digit: u8) -> i16 {
-    if digit >= 48 && digit <= 57 {
-        (digit as i16) - 48
-    } else {
-        0
-    }
-}
-
 fn sanitize(s: &str) -> String {
     let mut result = String::new();
     for c in s.trim().chars() {
@@ -220,6 +277,118 @@ impl fmt::Display for Attribution {
 mod tests {
     use super::Attribution;
 
+    // Each test uses its own role so that the environment
+    // variables don't collide when tests run in parallel.
+
+    #[test]
+    fn from_env_raw_date() {
+        std::env::set_var("GIT_RSGIT_TEST_RAW_NAME", "A U Thor");
+        std::env::set_var("GIT_RSGIT_TEST_RAW_EMAIL", "author@example.com");
+        std::env::set_var("GIT_RSGIT_TEST_RAW_DATE", "@1234567890 +0000");
+
+        let a = Attribution::from_env("rsgit_test_raw", None, None).unwrap();
+        assert_eq!(a.name(), "A U Thor");
+        assert_eq!(a.email(), "author@example.com");
+        assert_eq!(a.timestamp(), 1_234_567_890);
+        assert_eq!(a.tz_offset(), 0);
+
+        std::env::set_var("GIT_RSGIT_TEST_RAW_DATE", "1112911993 -0700");
+        let a = Attribution::from_env("rsgit_test_raw", None, None).unwrap();
+        assert_eq!(a.timestamp(), 1_112_911_993);
+        assert_eq!(a.tz_offset(), -420);
+
+        std::env::set_var("GIT_RSGIT_TEST_RAW_DATE", "Thu, 07 Apr 2005 22:13:13 +0200");
+        let a = Attribution::from_env("rsgit_test_raw", None, None).unwrap();
+        assert_eq!(a.timestamp(), 1_112_904_793);
+        assert_eq!(a.tz_offset(), 120);
+
+        for bad in [
+            "",
+            "@",
+            "1234567890",
+            "@12x4 +0000",
+            "@1234567890 +000",
+            "@1234567890 +0a00",
+            "@1234567890 +2000",
+            "1 +0000 extra",
+        ]
+        .iter()
+        {
+            std::env::set_var("GIT_RSGIT_TEST_RAW_DATE", bad);
+            assert!(
+                Attribution::from_env("rsgit_test_raw", None, None).is_none(),
+                "accepted date {:?}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn from_env_precedence() {
+        let config_name = Some("Config Name");
+        let config_email = Some("config@example.com");
+
+        std::env::set_var("GIT_RSGIT_TEST_PREC_DATE", "@1234567890 +0000");
+
+        let a = Attribution::from_env("rsgit_test_prec", config_name, config_email).unwrap();
+        assert_eq!(a.name(), "Config Name");
+        assert_eq!(a.email(), "config@example.com");
+
+        std::env::set_var("GIT_RSGIT_TEST_PREC_NAME", "Env Name");
+        let a = Attribution::from_env("rsgit_test_prec", config_name, config_email).unwrap();
+        assert_eq!(a.name(), "Env Name");
+        assert_eq!(a.email(), "config@example.com");
+
+        std::env::set_var("GIT_RSGIT_TEST_PREC_EMAIL", "env@example.com");
+        let a = Attribution::from_env("rsgit_test_prec", config_name, config_email).unwrap();
+        assert_eq!(a.name(), "Env Name");
+        assert_eq!(a.email(), "env@example.com");
+    }
+
+    #[test]
+    fn from_env_missing() {
+        assert!(Attribution::from_env("rsgit_test_missing", None, Some("x@example.com")).is_none());
+        assert!(Attribution::from_env("rsgit_test_missing", Some("X"), None).is_none());
+
+        // With no date, the current time is used.
+        let a =
+            Attribution::from_env("rsgit_test_missing", Some("X"), Some("x@example.com")).unwrap();
+        assert!(a.timestamp() > 1_600_000_000);
+        assert_eq!(a.tz_offset(), 0);
+    }
+
+    #[test]
+    fn parse_date() {
+        assert_eq!(
+            Attribution::parse_date("@1112911993 -0700"),
+            Some((1_112_911_993, -420))
+        );
+        assert_eq!(
+            Attribution::parse_date("2005-04-07T22:13:13+02:00"),
+            Some((1_112_904_793, 120))
+        );
+        assert_eq!(
+            Attribution::parse_date("Thu, 07 Apr 2005 15:13:13 -0700"),
+            Some((1_112_911_993, -420))
+        );
+        assert_eq!(Attribution::parse_date("next tuesday"), None);
+    }
+
+    #[test]
+    fn cmp_by_time() {
+        let mut v = [
+            Attribution::new("C", "c@example.com", 1_142_878_600, 0),
+            Attribution::new("A", "a@example.com", 1_142_878_501, 150),
+            Attribution::new("D", "d@example.com", 1_142_878_600, -300),
+            Attribution::new("B", "b@example.com", 1_142_878_550, 0),
+        ];
+
+        v.sort_by(Attribution::cmp_by_time);
+
+        let names: Vec<&str> = v.iter().map(|a| a.name()).collect();
+        assert_eq!(names, ["A", "B", "D", "C"]);
+    }
+
     #[test]
     fn happy_path() {
         let a = Attribution::new("A U Thor", "author@example.com", 1_142_878_501, 150);
@@ -280,6 +449,31 @@ mod tests {
         assert_eq!(a.tz_offset(), -420);
     }
 
+    #[test]
+    fn parse_tz_minutes() {
+        let a = Attribution::parse(b"Me <me@example.com> 1234567890 +0530\n").unwrap();
+        assert_eq!(a.tz_offset(), 330);
+        assert_eq!(a.format_tz(), "+0530");
+
+        let a = Attribution::parse(b"Me <me@example.com> 1234567890 -0345\n").unwrap();
+        assert_eq!(a.tz_offset(), -225);
+        assert_eq!(a.format_tz(), "-0345");
+    }
+
+    #[test]
+    fn parse_tz_with_colon() {
+        let a = Attribution::parse(b"Me <me@example.com> 1234567890 +05:30\n").unwrap();
+        assert_eq!(a.tz_offset(), 330);
+        assert_eq!(a.to_string(), "Me <me@example.com> 1234567890 +0530");
+
+        let a = Attribution::parse(b"Me <me@example.com> 1234567890 -07:00").unwrap();
+        assert_eq!(a.tz_offset(), -420);
+        assert_eq!(a.format_tz(), "-0700");
+
+        let a = Attribution::parse(b"Me <me@example.com> 1234567890 +05-30").unwrap();
+        assert_eq!(a.tz_offset(), 0);
+    }
+
     #[test]
     fn parse_fuzzy_cases() {
         let a = Attribution::parse(
@@ -56,6 +56,26 @@ pub(crate) fn commit_is_valid(s: &dyn ContentSource) -> ContentSourceResult<bool
 mod tests {
     use super::*;
 
+    use crate::object::parse_utils::MAX_LINE_LEN;
+
+    #[test]
+    fn error_overlong_header_line() {
+        // A multi-megabyte "line" with no newline.
+        let cs = "x".repeat(4 * MAX_LINE_LEN);
+        let err = commit_is_valid(&cs).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("line exceeds {} byte limit", MAX_LINE_LEN)
+        );
+
+        // The first line is fine; the second is too long.
+        let cs = format!(
+            "tree be9bfa841874ccc9f2ef7c48d0c76226f89b7189\n{}",
+            "x".repeat(MAX_LINE_LEN + 1)
+        );
+        assert!(commit_is_valid(&cs).is_err());
+    }
+
     #[test]
     fn invalid_empty() {
         let cs = "".to_string();
@@ -13,6 +13,7 @@ pub(crate) fn tree_is_valid(s: &dyn ContentSource) -> ContentSourceResult<bool>
         &CheckPlatforms {
             windows: false,
             mac: false,
+            ntfs_segment_length: false,
         },
     )
 }
@@ -213,6 +214,21 @@ mod tests {
         assert_eq!(tree_is_valid(&cs).unwrap(), false);
     }
 
+    #[test]
+    fn valid_gitlink_to_unknown_commit() {
+        // A gitlink names a commit in another (sub)module's repository,
+        // so there's no expectation that the object exists. Only the
+        // null ID is rejected, as for any other entry.
+        let cs = entry_with_object_id("160000 submodule", "submodule-commit-id!");
+        assert_eq!(tree_is_valid(&cs).unwrap(), true);
+
+        let cs = entry_with_object_id(
+            "160000 submodule",
+            "\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+        );
+        assert_eq!(tree_is_valid(&cs).unwrap(), false);
+    }
+
     fn check_one_name(name: &str) {
         let mut mode_name = "100644 ".to_string();
         mode_name.push_str(name);
@@ -284,6 +300,21 @@ mod tests {
         assert_eq!(tree_is_valid(&cs).unwrap(), false);
     }
 
+    #[test]
+    fn valid_mode_group_writable() {
+        let cs = entry("100664 a");
+        assert!(tree_is_valid(&cs).unwrap());
+    }
+
+    #[test]
+    fn invalid_mode_non_canonical_permissions() {
+        let cs = entry("100640 a");
+        assert!(!tree_is_valid(&cs).unwrap());
+
+        let cs = entry("100600 a");
+        assert!(!tree_is_valid(&cs).unwrap());
+    }
+
     #[test]
     fn invalid_mode_not_octal() {
         let cs = entry("8 a");
@@ -363,7 +394,8 @@ mod tests {
                     &cs,
                     &CheckPlatforms {
                         windows: false,
-                        mac: true
+                        mac: true,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap(),
@@ -386,7 +418,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -405,7 +438,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -421,7 +455,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -434,7 +469,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -554,7 +590,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -566,7 +603,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -629,7 +667,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -641,7 +680,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -659,7 +699,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -671,7 +712,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -689,7 +731,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -701,7 +744,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -719,7 +763,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -731,7 +776,8 @@ mod tests {
                 &cs,
                 &CheckPlatforms {
                     windows: false,
-                    mac: true
+                    mac: true,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap(),
@@ -758,7 +804,8 @@ mod tests {
                     &cs,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap(),
@@ -770,7 +817,8 @@ mod tests {
                     &cs,
                     &CheckPlatforms {
                         windows: false,
-                        mac: true
+                        mac: true,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap(),
@@ -799,7 +847,8 @@ mod tests {
                     &cs,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap(),
@@ -811,7 +860,8 @@ mod tests {
                     &cs,
                     &CheckPlatforms {
                         windows: false,
-                        mac: true
+                        mac: true,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap(),
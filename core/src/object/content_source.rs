@@ -3,6 +3,8 @@ use std::{
     vec::Vec,
 };
 
+use thiserror::Error;
+
 /// Result type for operations which depend on [`ContentSource.open()`].
 /// Since [`ContentSource`] may wrap arbitrary sources,
 /// it could return any arbitrary error type.
@@ -19,13 +21,30 @@ pub type ContentSourceResult<T> = std::result::Result<T, Box<dyn std::error::Err
 /// [`ContentSource.open()`]: trait.ContentSource.html#tymethod.open
 pub type ContentSourceOpenResult<'a> = ContentSourceResult<Box<dyn BufRead + 'a>>;
 
+/// Error returned when a [`ContentSource`] provides a different number
+/// of bytes than it reports via [`len()`].
+///
+/// [`ContentSource`]: trait.ContentSource.html
+/// [`len()`]: trait.ContentSource.html#tymethod.len
+#[derive(Debug, Eq, Error, PartialEq)]
+#[error("content source reported {expected} bytes, but provided {actual} bytes")]
+pub struct ContentLengthMismatch {
+    pub expected: u64,
+    pub actual: u64,
+}
+
 /// Trait used for reading git object content from various sources.
 pub trait ContentSource {
     // TO DO: Rework this as async at some point? I'm not ready for that yet.
     // https://github.com/rust-git/rsgit/issues/18
 
     /// Returns the length (in bytes) of the content.
-    fn len(&self) -> usize;
+    ///
+    /// This is called frequently (e.g. to build object headers and to
+    /// answer size queries), so implementations must be able to answer
+    /// without opening or reading the content. Compute or cache the length
+    /// when the source is constructed instead.
+    fn len(&self) -> u64;
 
     /// Returns true if the content is empty.
     fn is_empty(&self) -> bool {
@@ -39,8 +58,8 @@ pub trait ContentSource {
 }
 
 impl ContentSource for Vec<u8> {
-    fn len(&self) -> usize {
-        self.len()
+    fn len(&self) -> u64 {
+        Vec::len(self) as u64
     }
 
     fn open(&self) -> ContentSourceOpenResult {
@@ -48,9 +67,19 @@ impl ContentSource for Vec<u8> {
     }
 }
 
+impl ContentSource for &[u8] {
+    fn len(&self) -> u64 {
+        <[u8]>::len(self) as u64
+    }
+
+    fn open(&self) -> ContentSourceOpenResult {
+        Ok(Box::new(Cursor::new(*self)))
+    }
+}
+
 impl ContentSource for String {
-    fn len(&self) -> usize {
-        self.len()
+    fn len(&self) -> u64 {
+        String::len(self) as u64
     }
 
     fn open(&self) -> ContentSourceOpenResult {
@@ -111,6 +140,34 @@ mod tests {
         assert_eq!(buf, [67, 3, 45]);
     }
 
+    #[test]
+    fn slice_with_content() {
+        let v = vec![2, 3, 45, 67];
+        let s: &[u8] = &v;
+
+        let l = ContentSource::len(&s);
+        assert_eq!(l, 4);
+
+        assert!(!ContentSource::is_empty(&s));
+
+        let mut buf = [0; 3];
+        let mut f = s.open().unwrap();
+
+        let r = f.read(&mut buf);
+        assert!(r.is_ok());
+        assert_eq!(r.unwrap(), 3);
+        assert_eq!(buf, [2, 3, 45]);
+
+        let r = f.read(&mut buf);
+        assert!(r.is_ok());
+        assert_eq!(r.unwrap(), 1);
+        assert_eq!(buf, [67, 3, 45]);
+
+        let r = f.read(&mut buf);
+        assert!(r.is_ok());
+        assert_eq!(r.unwrap(), 0);
+    }
+
     #[test]
     fn empty_str() {
         let s = "".to_string();
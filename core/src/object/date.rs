@@ -0,0 +1,313 @@
+// Parsing for the date formats that command-line git accepts in
+// `GIT_AUTHOR_DATE`, `GIT_COMMITTER_DATE`, and `--date`.
+//
+// See https://git-scm.com/docs/git-commit#_date_formats.
+
+// Parse a date in one of the following forms, returning the Unix
+// timestamp and time zone offset (in minutes):
+//
+// * git's raw format: `<timestamp> <tz>`, optionally with a leading `@`
+//   (`@1112911993 -0700`)
+// * ISO 8601: `2005-04-07T22:13:13+02:00`, optionally with a space
+//   instead of `T`, `Z` for UTC, or the time zone as `+HHMM` after a space
+// * RFC 2822: `Thu, 07 Apr 2005 22:13:13 +0200` (the weekday is optional)
+pub(crate) fn parse_date(s: &str) -> Option<(i64, i16)> {
+    let s = s.trim();
+    parse_raw(s)
+        .or_else(|| parse_iso_8601(s))
+        .or_else(|| parse_rfc_2822(s))
+}
+
+fn parse_raw(s: &str) -> Option<(i64, i16)> {
+    let mut words = s.split_whitespace();
+    let timestamp = words.next()?;
+    let tz = parse_tz(words.next()?, true)?;
+    if words.next().is_some() {
+        return None;
+    }
+
+    let timestamp = timestamp.strip_prefix('@').unwrap_or(timestamp);
+    Some((parse_number(timestamp)?, tz))
+}
+
+fn parse_iso_8601(s: &str) -> Option<(i64, i16)> {
+    // YYYY-MM-DD[T ]HH:MM:SS, then the time zone.
+    if s.len() < 19 || !s.is_char_boundary(19) {
+        return None;
+    }
+
+    let (date_time, tz) = s.split_at(19);
+    let b = date_time.as_bytes();
+    if !date_time.is_ascii() || b[4] != b'-' || b[7] != b'-' || (b[10] != b'T' && b[10] != b' ') {
+        return None;
+    }
+
+    let year = parse_number(&date_time[0..4])?;
+    let month = parse_number(&date_time[5..7])?;
+    let day = parse_number(&date_time[8..10])?;
+    let seconds = parse_time(&date_time[11..19])?;
+
+    let tz = match tz.trim_start() {
+        "Z" => 0,
+        tz => parse_tz(tz, true)?,
+    };
+
+    Some((to_timestamp(year, month, day, seconds, tz)?, tz))
+}
+
+fn parse_rfc_2822(s: &str) -> Option<(i64, i16)> {
+    // [Ddd, ]DD Mmm YYYY HH:MM:SS +HHMM
+    let s = match s.find(',') {
+        Some(n) => &s[n + 1..],
+        None => s,
+    };
+
+    let mut words = s.split_whitespace();
+    let day = parse_number(words.next()?)?;
+    let month = parse_month(words.next()?)?;
+    let year = parse_number(words.next()?)?;
+    let seconds = parse_time(words.next()?)?;
+    let tz = parse_tz(words.next()?, true)?;
+    if words.next().is_some() {
+        return None;
+    }
+
+    Some((to_timestamp(year, month, day, seconds, tz)?, tz))
+}
+
+fn parse_number(s: &str) -> Option<i64> {
+    if s.is_empty() || s.len() > 18 || !s.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+// Parse `HH:MM:SS` into seconds since midnight.
+fn parse_time(s: &str) -> Option<i64> {
+    let b = s.as_bytes();
+    if b.len() != 8 || !s.is_ascii() || b[2] != b':' || b[5] != b':' {
+        return None;
+    }
+
+    let hh = parse_number(&s[0..2])?;
+    let mm = parse_number(&s[3..5])?;
+    let ss = parse_number(&s[6..8])?;
+    if hh > 23 || mm > 59 || ss > 60 {
+        return None;
+    }
+
+    Some(hh * 3600 + mm * 60 + ss)
+}
+
+// Parse git's `+HHMM` form, or the ISO 8601 `+HH:MM` form that some
+// tools emit, into minutes east of UTC.
+//
+// If `strict` is set, the digits must be valid, the minutes must be at
+// most 59, and the offset must be in the range that `Attribution::new`
+// accepts. Otherwise, as when parsing existing commits and tags, invalid
+// digits are read as 0 and any offset is accepted.
+pub(crate) fn parse_tz(s: &str, strict: bool) -> Option<i16> {
+    let s = s.as_bytes();
+
+    let (sign, hh, mm) = match s.len() {
+        5 => (s[0], &s[1..3], &s[3..5]),
+        6 if s[3] == b':' => (s[0], &s[1..3], &s[4..6]),
+        _ => return None,
+    };
+
+    let sign = match sign {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+
+    if strict && !hh.iter().chain(mm).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let hh = from_digit(hh[0]) * 10 + from_digit(hh[1]);
+    let mm = from_digit(mm[0]) * 10 + from_digit(mm[1]);
+    let tz = sign * (hh * 60 + mm);
+
+    if strict && (mm > 59 || !(-720..=840).contains(&tz)) {
+        return None;
+    }
+
+    Some(tz)
+}
+
+fn from_digit(digit: u8) -> i16 {
+    if digit.is_ascii_digit() {
+        (digit - b'0') as i16
+    } else {
+        0
+    }
+}
+
+fn parse_month(s: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+
+    let s = s.to_ascii_lowercase();
+    MONTHS.iter().position(|m| *m == s).map(|n| n as i64 + 1)
+}
+
+fn to_timestamp(year: i64, month: i64, day: i64, seconds: i64, tz: i16) -> Option<i64> {
+    // Bounding the year keeps the calendar math below from overflowing.
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) {
+        return None;
+    }
+    if day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let local = days_from_civil(year, month, day) * 86400 + seconds;
+    Some(local - tz as i64 * 60)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 for a date in the proleptic Gregorian calendar.
+// From Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw() {
+        assert_eq!(parse_date("@1112911993 -0700"), Some((1_112_911_993, -420)));
+        assert_eq!(parse_date("1112911993 +0200"), Some((1_112_911_993, 120)));
+        assert_eq!(parse_date("@0 +0000"), Some((0, 0)));
+
+        assert_eq!(parse_date("@1112911993"), None);
+        assert_eq!(parse_date("@1112911993 +000"), None);
+        assert_eq!(parse_date("@11129x1993 +0000"), None);
+    }
+
+    #[test]
+    fn iso_8601() {
+        assert_eq!(
+            parse_date("2005-04-07T22:13:13+02:00"),
+            Some((1_112_904_793, 120))
+        );
+        assert_eq!(
+            parse_date("2005-04-07 22:13:13 +0200"),
+            Some((1_112_904_793, 120))
+        );
+        assert_eq!(
+            parse_date("2005-04-07T15:13:13-0700"),
+            Some((1_112_911_993, -420))
+        );
+        assert_eq!(parse_date("2005-04-07T22:13:13Z"), Some((1_112_911_993, 0)));
+        assert_eq!(parse_date("2000-02-29T00:00:00Z"), Some((951_782_400, 0)));
+
+        assert_eq!(parse_date("2005-04-07T22:13"), None);
+        assert_eq!(parse_date("2005-13-07T22:13:13Z"), None);
+        assert_eq!(parse_date("2005-04-07T25:13:13Z"), None);
+        assert_eq!(parse_date("2005-04-07T22:13:13"), None);
+    }
+
+    #[test]
+    fn rfc_2822() {
+        assert_eq!(
+            parse_date("Thu, 07 Apr 2005 22:13:13 +0200"),
+            Some((1_112_904_793, 120))
+        );
+        assert_eq!(
+            parse_date("7 Apr 2005 15:13:13 -0700"),
+            Some((1_112_911_993, -420))
+        );
+        assert_eq!(
+            parse_date("Sat, 01 Jan 2000 00:00:00 -1200"),
+            Some((946_728_000, -720))
+        );
+
+        assert_eq!(parse_date("Thu, 07 Foo 2005 22:13:13 +0200"), None);
+        assert_eq!(parse_date("Thu, 07 Apr 2005 22:13:13"), None);
+    }
+
+    #[test]
+    fn nonexistent_dates() {
+        assert_eq!(parse_date("31 Feb 2005 00:00:00 +0000"), None);
+        assert_eq!(parse_date("29 Feb 2005 00:00:00 +0000"), None);
+        assert_eq!(parse_date("31 Apr 2005 00:00:00 +0000"), None);
+        assert_eq!(parse_date("2005-06-31T00:00:00Z"), None);
+        assert_eq!(parse_date("1900-02-29T00:00:00Z"), None);
+
+        assert_eq!(
+            parse_date("29 Feb 2004 00:00:00 +0000"),
+            Some((1_078_012_800, 0))
+        );
+        assert_eq!(
+            parse_date("31 Dec 2005 00:00:00 +0000"),
+            Some((1_135_987_200, 0))
+        );
+    }
+
+    #[test]
+    fn year_out_of_range() {
+        assert_eq!(parse_date("1 Jan 999999999999999999 00:00:00 +0000"), None);
+        assert_eq!(parse_date("1 Jan 10000 00:00:00 +0000"), None);
+        assert_eq!(
+            parse_date("31 Dec 9999 23:59:59 +0000"),
+            Some((253_402_300_799, 0))
+        );
+    }
+
+    #[test]
+    fn tz_range() {
+        assert_eq!(parse_date("@0 +1400"), Some((0, 840)));
+        assert_eq!(parse_date("@0 -1200"), Some((0, -720)));
+        assert_eq!(parse_date("@0 +1401"), None);
+        assert_eq!(parse_date("@0 -1201"), None);
+        assert_eq!(parse_date("@0 +0099"), None);
+        assert_eq!(parse_date("@0 +0060"), None);
+        assert_eq!(parse_date("@0 +0059"), Some((0, 59)));
+        assert_eq!(parse_date("2005-04-07T22:13:13+01:75"), None);
+    }
+
+    #[test]
+    fn parse_tz_lenient() {
+        assert_eq!(parse_tz("+0200", false), Some(120));
+        assert_eq!(parse_tz("-07:00", false), Some(-420));
+        assert_eq!(parse_tz("+0099", false), Some(99));
+        assert_eq!(parse_tz("+2500", false), Some(1500));
+        assert_eq!(parse_tz("+0x00", false), Some(0));
+
+        assert_eq!(parse_tz("0200", false), None);
+        assert_eq!(parse_tz("+020", false), None);
+        assert_eq!(parse_tz("+02-00", false), None);
+    }
+
+    #[test]
+    fn garbage() {
+        assert_eq!(parse_date(""), None);
+        assert_eq!(parse_date("yesterday"), None);
+        assert_eq!(parse_date("2005-04-07T22:13:13+02:00 extra"), None);
+        assert_eq!(
+            parse_date("\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}"),
+            None
+        );
+        assert_eq!(parse_date("@0 +0\u{e9}0"), None);
+        assert_eq!(parse_date("2005-04-07T22:13:1\u{e9}Z"), None);
+        assert_eq!(parse_date("7 Apr 2005 15:13:1\u{e9} -0700"), None);
+    }
+}
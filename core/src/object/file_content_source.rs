@@ -12,9 +12,15 @@ use crate::object::{ContentSource, ContentSourceOpenResult};
 /// [`ContentSource`]: trait.ContentSource.html
 pub struct FileContentSource {
     path: PathBuf,
-    len: usize,
+    len: u64,
+    buffer_size: usize,
 }
 
+/// The default size of the read buffer used by [`FileContentSource`].
+///
+/// [`FileContentSource`]: struct.FileContentSource.html
+pub const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
 impl FileContentSource {
     /// Create a `FileContentSource` for a file that exists
     /// already on disk.
@@ -25,20 +31,33 @@ impl FileContentSource {
         }
 
         Ok(FileContentSource {
-            len: m.len() as usize,
+            len: m.len(),
             path: path.as_ref().to_owned(),
+            buffer_size: DEFAULT_BUFFER_SIZE,
         })
     }
+
+    /// Set the size of the buffer used when reading the file.
+    ///
+    /// Object hashing and writing consume content one buffer at a time,
+    /// so a larger buffer may improve throughput for very large files.
+    /// The default is [`DEFAULT_BUFFER_SIZE`].
+    ///
+    /// [`DEFAULT_BUFFER_SIZE`]: constant.DEFAULT_BUFFER_SIZE.html
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> FileContentSource {
+        self.buffer_size = buffer_size.max(1);
+        self
+    }
 }
 
 impl ContentSource for FileContentSource {
-    fn len(&self) -> usize {
+    fn len(&self) -> u64 {
         self.len
     }
 
     fn open(&self) -> ContentSourceOpenResult {
         let f = File::open(&self.path)?;
-        Ok(Box::new(BufReader::new(f)))
+        Ok(Box::new(BufReader::with_capacity(self.buffer_size, f)))
     }
 }
 
@@ -70,6 +89,27 @@ mod tests {
         assert_eq!(&buf[..7], b"example");
     }
 
+    #[test]
+    fn buffer_size() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.as_ref().join("example");
+        fs::write(&path, b"example").unwrap();
+
+        let fcs = FileContentSource::new(&path).unwrap().with_buffer_size(3);
+        assert_eq!(fcs.len(), 7);
+
+        let mut r = fcs.open().unwrap();
+        assert_eq!(r.fill_buf().unwrap(), b"exa");
+
+        let mut content = Vec::new();
+        r.read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"example");
+
+        let fcs = FileContentSource::new(&path).unwrap().with_buffer_size(0);
+        let mut r = fcs.open().unwrap();
+        assert_eq!(r.fill_buf().unwrap(), b"e");
+    }
+
     #[test]
     fn not_existing_file() {
         let dir = TempDir::new().unwrap();
@@ -34,7 +34,7 @@ pub enum ParseIdError {
 
 /// An object ID is a string that identifies an object within a repository.
 /// It is stored as a 20-byte signature, but can also be represented as 40 hex digits.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Id {
     id: Vec<u8>,
 }
@@ -92,6 +92,17 @@ impl Id {
     }
 }
 
+impl From<[u8; 20]> for Id {
+    /// Create a new ID from a 20-byte array (e.g. the output of a SHA-1 hash).
+    ///
+    /// Unlike [`Id::new()`], this can not fail.
+    ///
+    /// [`Id::new()`]: #method.new
+    fn from(id: [u8; 20]) -> Self {
+        Id { id: id.to_vec() }
+    }
+}
+
 impl FromStr for Id {
     type Err = ParseIdError;
 
@@ -151,6 +162,22 @@ mod tests {
         assert_eq!(Id::new(&b).unwrap_err(), ParseIdError::Overflow);
     }
 
+    #[test]
+    fn from_array() {
+        let b: [u8; 20] = [
+            0x3c, 0xd9, 0x32, 0x9a, 0xc5, 0x36, 0x13, 0xa0, 0xbf, 0xa1, 0x98, 0xae, 0x28, 0xf3,
+            0xaf, 0x95, 0x7e, 0x49, 0x57, 0x3c,
+        ];
+
+        let oid = Id::from(b);
+        assert_eq!(oid.to_string(), "3cd9329ac53613a0bfa198ae28f3af957e49573c");
+        assert_eq!(oid, Id::new(&b).unwrap());
+        assert_eq!(
+            oid,
+            Id::from_hex("3cd9329ac53613a0bfa198ae28f3af957e49573c").unwrap()
+        );
+    }
+
     #[test]
     fn from_hex() {
         let oid = Id::from_hex("3cd9329ac53613a0bfa198ae28f3af957e49573c".as_bytes()).unwrap();
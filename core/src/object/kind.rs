@@ -16,6 +16,38 @@ pub enum Kind {
     Other(Vec<u8>),
 }
 
+impl Kind {
+    /// Returns the 3-bit type code used for this kind in pack file entries.
+    ///
+    /// Returns `None` for `Kind::Other`, which can't be stored in a pack.
+    /// (Codes 6 and 7 denote `OFS_DELTA` and `REF_DELTA` entries, which
+    /// are not object kinds.) See [pack format].
+    ///
+    /// [pack format]: https://git-scm.com/docs/pack-format#_object_types
+    pub fn pack_type_id(&self) -> Option<u8> {
+        match self {
+            Kind::Commit => Some(1),
+            Kind::Tree => Some(2),
+            Kind::Blob => Some(3),
+            Kind::Tag => Some(4),
+            Kind::Other(_) => None,
+        }
+    }
+
+    /// Returns the kind for a pack file entry's 3-bit type code.
+    ///
+    /// Returns `None` for delta entries (6 and 7) and unassigned codes.
+    pub fn from_pack_type_id(id: u8) -> Option<Kind> {
+        match id {
+            1 => Some(Kind::Commit),
+            2 => Some(Kind::Tree),
+            3 => Some(Kind::Blob),
+            4 => Some(Kind::Tag),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Kind {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
@@ -49,4 +81,27 @@ mod tests {
         let k = Kind::Other(b"arbitrary".to_vec());
         assert_eq!(k.to_string(), "arbitrary");
     }
+
+    #[test]
+    fn pack_type_id() {
+        assert_eq!(Kind::Commit.pack_type_id(), Some(1));
+        assert_eq!(Kind::Tree.pack_type_id(), Some(2));
+        assert_eq!(Kind::Blob.pack_type_id(), Some(3));
+        assert_eq!(Kind::Tag.pack_type_id(), Some(4));
+        assert_eq!(Kind::Other(b"arbitrary".to_vec()).pack_type_id(), None);
+    }
+
+    #[test]
+    fn from_pack_type_id() {
+        for k in [Kind::Commit, Kind::Tree, Kind::Blob, Kind::Tag].iter() {
+            assert_eq!(
+                Kind::from_pack_type_id(k.pack_type_id().unwrap()).unwrap(),
+                *k
+            );
+        }
+
+        for id in [0, 5, 6, 7, 8].iter() {
+            assert!(Kind::from_pack_type_id(*id).is_none());
+        }
+    }
 }
@@ -0,0 +1,217 @@
+use std::str;
+
+use crate::object::Attribution;
+
+/// A `Mailmap` maps the names and email addresses recorded in commits
+/// and tags to canonical identities, as described by a [`.mailmap`] file.
+///
+/// Each line of a mailmap takes one of the following forms:
+///
+/// ```text
+/// Proper Name <commit@email>
+/// <proper@email> <commit@email>
+/// Proper Name <proper@email> <commit@email>
+/// Proper Name <proper@email> Commit Name <commit@email>
+/// ```
+///
+/// Names and email addresses are matched case-insensitively. Text after
+/// a `#` at the start of a line, or after the last `>` on a line,
+/// is ignored.
+///
+/// [`.mailmap`]: https://git-scm.com/docs/gitmailmap
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Mailmap {
+    entries: Vec<MailmapEntry>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct MailmapEntry {
+    proper_name: Option<String>,
+    proper_email: Option<String>,
+    commit_name: Option<String>,
+    commit_email: String,
+}
+
+impl Mailmap {
+    /// Parse the content of a `.mailmap` file.
+    ///
+    /// Lines which can't be understood (or aren't valid UTF-8)
+    /// are skipped, as command-line git does.
+    pub fn parse(content: &[u8]) -> Mailmap {
+        let entries = content
+            .split(|c| *c == b'\n')
+            .filter_map(|line| str::from_utf8(line).ok())
+            .filter_map(parse_line)
+            .collect();
+
+        Mailmap { entries }
+    }
+
+    /// Returns `true` if the mailmap has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Rewrite the name and/or email of `attribution` according to
+    /// this mailmap. Timestamp and time zone are unchanged.
+    ///
+    /// An entry which names both the commit name and email takes precedence
+    /// over one which names only the email. If there are several entries
+    /// of the same kind, the last one wins.
+    pub fn resolve(&self, attribution: &Attribution) -> Attribution {
+        let email_matches =
+            |e: &&MailmapEntry| e.commit_email.eq_ignore_ascii_case(attribution.email());
+
+        let entry = self
+            .entries
+            .iter()
+            .rev()
+            .filter(email_matches)
+            .find(|e| match &e.commit_name {
+                Some(name) => name.to_lowercase() == attribution.name().to_lowercase(),
+                None => false,
+            })
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .rev()
+                    .filter(email_matches)
+                    .find(|e| e.commit_name.is_none())
+            });
+
+        match entry {
+            Some(entry) => attribution.with_identity(
+                entry.proper_name.as_deref().unwrap_or(attribution.name()),
+                entry.proper_email.as_deref().unwrap_or(attribution.email()),
+            ),
+            None => attribution.clone(),
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<MailmapEntry> {
+    if line.trim_start().starts_with('#') {
+        return None;
+    }
+
+    let (name1, email1, rest) = parse_name_and_email(line)?;
+
+    match parse_name_and_email(rest) {
+        Some((name2, email2, _)) => Some(MailmapEntry {
+            proper_name: name1,
+            proper_email: Some(email1),
+            commit_name: name2,
+            commit_email: email2,
+        }),
+        None => Some(MailmapEntry {
+            proper_name: name1,
+            proper_email: None,
+            commit_name: None,
+            commit_email: email1,
+        }),
+    }
+}
+
+// Parse `Name <email>` from the start of `s`. The name may be empty.
+// Returns the name, email, and remainder of `s` after the closing `>`.
+fn parse_name_and_email(s: &str) -> Option<(Option<String>, String, &str)> {
+    let open = s.find('<')?;
+    let close = open + s[open..].find('>')?;
+
+    let name = s[..open].trim();
+    let name = if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    };
+
+    let email = s[open + 1..close].trim().to_string();
+
+    Some((name, email, &s[close + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(mailmap: &Mailmap, name: &str, email: &str) -> (String, String) {
+        let a = Attribution::new(name, email, 1_142_878_501, 150);
+        let r = mailmap.resolve(&a);
+
+        assert_eq!(r.timestamp(), a.timestamp());
+        assert_eq!(r.tz_offset(), a.tz_offset());
+
+        (r.name().to_string(), r.email().to_string())
+    }
+
+    #[test]
+    fn empty() {
+        let m = Mailmap::parse(b"");
+        assert!(m.is_empty());
+        assert_eq!(
+            resolve(&m, "A U Thor", "author@example.com"),
+            ("A U Thor".to_string(), "author@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn name_only() {
+        let m = Mailmap::parse(b"Proper Name <commit@example.com>\n");
+        assert!(!m.is_empty());
+        assert_eq!(
+            resolve(&m, "some name", "Commit@Example.com"),
+            ("Proper Name".to_string(), "Commit@Example.com".to_string())
+        );
+        assert_eq!(
+            resolve(&m, "some name", "other@example.com"),
+            ("some name".to_string(), "other@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn email_only() {
+        let m = Mailmap::parse(b"<proper@example.com> <commit@example.com>\n");
+        assert_eq!(
+            resolve(&m, "some name", "commit@example.com"),
+            ("some name".to_string(), "proper@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn name_and_email() {
+        let m = Mailmap::parse(b"Proper Name <proper@example.com> <commit@example.com>\n");
+        assert_eq!(
+            resolve(&m, "some name", "commit@example.com"),
+            ("Proper Name".to_string(), "proper@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn name_and_email_with_commit_name() {
+        let m = Mailmap::parse(
+            b"# comment\n\
+              Proper Name <proper@example.com> Commit Name <commit@example.com>\n\
+              Other Name <other@example.com> <commit@example.com> # trailing comment\n\
+              \n\
+              garbage line\n",
+        );
+
+        assert_eq!(
+            resolve(&m, "commit name", "commit@example.com"),
+            ("Proper Name".to_string(), "proper@example.com".to_string())
+        );
+        assert_eq!(
+            resolve(&m, "Someone Else", "commit@example.com"),
+            ("Other Name".to_string(), "other@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn last_entry_wins() {
+        let m = Mailmap::parse(b"First <commit@example.com>\nSecond <commit@example.com>\n");
+        assert_eq!(
+            resolve(&m, "x", "commit@example.com"),
+            ("Second".to_string(), "commit@example.com".to_string())
+        );
+    }
+}
@@ -1,6 +1,8 @@
 //! Represents the git concept of an "object" which is a tuple of
 //! object type and binary data identified by the hash of the binary data.
 
+use std::hash::{Hash, Hasher};
+
 use crate::path::CheckPlatforms;
 
 use sha1::{Digest, Sha1};
@@ -13,10 +15,14 @@ mod check_tag;
 mod check_tree;
 
 mod content_source;
-pub use content_source::{ContentSource, ContentSourceOpenResult, ContentSourceResult};
+pub use content_source::{
+    ContentLengthMismatch, ContentSource, ContentSourceOpenResult, ContentSourceResult,
+};
+
+mod date;
 
 mod file_content_source;
-pub use file_content_source::FileContentSource;
+pub use file_content_source::{FileContentSource, DEFAULT_BUFFER_SIZE};
 
 mod id;
 pub use id::{Id, ParseIdError};
@@ -24,8 +30,14 @@ pub use id::{Id, ParseIdError};
 mod kind;
 pub use kind::Kind;
 
+mod mailmap;
+pub use mailmap::Mailmap;
+
 pub(crate) mod parse_utils;
 
+mod progress_reader;
+pub use progress_reader::ProgressReader;
+
 mod read_content_source;
 pub use read_content_source::ReadContentSource;
 
@@ -33,18 +45,24 @@ pub use read_content_source::ReadContentSource;
 ///
 /// This struct is constructed, modified, and shared as a working description of
 /// how to find and describe an object before it gets written to a repository.
-pub struct Object {
+///
+/// The lifetime `'a` allows the content source to borrow its data
+/// (e.g. a `&[u8]`) rather than own it.
+pub struct Object<'a> {
     id: Id,
     kind: Kind,
-    content_source: Box<dyn ContentSource>,
+    content_source: Box<dyn ContentSource + 'a>,
 }
 
-impl Object {
+impl<'a> Object<'a> {
     /// Create a new Object.
     ///
     /// Calculates the object's ID.
     #[cfg(not(tarpaulin_include))]
-    pub fn new(kind: &Kind, content_source: Box<dyn ContentSource>) -> ContentSourceResult<Object> {
+    pub fn new(
+        kind: &Kind,
+        content_source: Box<dyn ContentSource + 'a>,
+    ) -> ContentSourceResult<Object<'a>> {
         Ok(Object {
             id: assign_id(kind, content_source.as_ref())?,
             kind: kind.clone(),
@@ -52,6 +70,34 @@ impl Object {
         })
     }
 
+    /// Create a new blob Object.
+    ///
+    /// Equivalent to `Object::new(&Kind::Blob, content_source)`.
+    pub fn blob(content_source: Box<dyn ContentSource + 'a>) -> ContentSourceResult<Object<'a>> {
+        Object::new(&Kind::Blob, content_source)
+    }
+
+    /// Create a new commit Object.
+    ///
+    /// Equivalent to `Object::new(&Kind::Commit, content_source)`.
+    pub fn commit(content_source: Box<dyn ContentSource + 'a>) -> ContentSourceResult<Object<'a>> {
+        Object::new(&Kind::Commit, content_source)
+    }
+
+    /// Create a new tree Object.
+    ///
+    /// Equivalent to `Object::new(&Kind::Tree, content_source)`.
+    pub fn tree(content_source: Box<dyn ContentSource + 'a>) -> ContentSourceResult<Object<'a>> {
+        Object::new(&Kind::Tree, content_source)
+    }
+
+    /// Create a new tag Object.
+    ///
+    /// Equivalent to `Object::new(&Kind::Tag, content_source)`.
+    pub fn tag(content_source: Box<dyn ContentSource + 'a>) -> ContentSourceResult<Object<'a>> {
+        Object::new(&Kind::Tag, content_source)
+    }
+
     /// Return the ID of the object.
     #[cfg(not(tarpaulin_include))]
     pub fn id(&self) -> &Id {
@@ -66,10 +112,18 @@ impl Object {
     }
 
     /// Return the size (in bytes) of the object.
-    pub fn len(&self) -> usize {
+    ///
+    /// This never opens or reads the object's content.
+    pub fn len(&self) -> u64 {
         self.content_source.len()
     }
 
+    /// Returns the header which precedes the object's content when it is
+    /// hashed or stored (e.g. `blob 13\0`).
+    pub fn header(&self) -> Vec<u8> {
+        header(&self.kind, self.len())
+    }
+
     /// Returns `true` if the object is empty.
     pub fn is_empty(&self) -> bool {
         self.len() == 0
@@ -83,7 +137,23 @@ impl Object {
         self.content_source.open()
     }
 
+    /// Like [`open()`], but wraps the reader in a [`ProgressReader`] which
+    /// counts the bytes read so far (e.g. for progress reporting).
+    ///
+    /// [`open()`]: #method.open
+    /// [`ProgressReader`]: struct.ProgressReader.html
+    pub fn open_with_progress(
+        &self,
+    ) -> ContentSourceResult<ProgressReader<Box<dyn std::io::BufRead + '_>>> {
+        Ok(ProgressReader::new(self.content_source.open()?))
+    }
+
     /// Returns `true` if the content of the object is valid for the type.
+    ///
+    /// Only the object's own content is checked. Objects that it refers to
+    /// (a commit's tree and parents, a tree's entries) aren't required to
+    /// exist. This matters in particular for gitlink (`160000`) tree entries,
+    /// which name a commit in a submodule's repository rather than this one.
     pub fn is_valid(&self) -> ContentSourceResult<bool> {
         match self.kind {
             Kind::Blob => Ok(true),
@@ -116,40 +186,74 @@ impl Object {
     }
 }
 
+// Objects are compared and hashed by ID alone. Since the ID is a hash of
+// the kind and content, two objects with the same ID have the same content
+// even if they are backed by different content sources.
+
+impl PartialEq for Object<'_> {
+    fn eq(&self, other: &Object) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Object<'_> {}
+
+impl Hash for Object<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+// Sizes are always `u64` (as in git) so that objects larger than 4GB
+// are described correctly even on 32-bit platforms.
+fn header(kind: &Kind, len: u64) -> Vec<u8> {
+    format!("{} {}\0", kind, len).into_bytes()
+}
+
 fn assign_id(kind: &Kind, content_source: &dyn ContentSource) -> ContentSourceResult<Id> {
     let mut hasher = Sha1::new();
 
-    hasher.update(kind.to_string());
-    hasher.update(b" ");
-
-    let lstr = content_source.len().to_string();
-    hasher.update(lstr);
-    hasher.update(b"\0");
+    let len = content_source.len();
+    hasher.update(header(kind, len));
 
     {
+        // Hash directly from the content source's own buffer so the
+        // source (not this function) determines the read size.
         let mut reader = content_source.open()?;
-        let mut buf = [0; 8192];
-        let mut n = 1;
+        let mut actual = 0;
 
-        while n > 0 {
-            n = reader.read(&mut buf)?;
-            if n > 0 {
-                hasher.update(&buf[..n]);
+        loop {
+            let buf = reader.fill_buf()?;
+            let n = buf.len();
+            if n == 0 {
+                break;
             }
+
+            hasher.update(buf);
+            reader.consume(n);
+            actual += n as u64;
         }
-    }
 
-    let final_hash = hasher.finalize();
-    let id: &[u8] = final_hash.as_slice();
+        // The header written above uses the reported length. If the content
+        // doesn't match, the resulting object would be corrupt.
+        if actual != len {
+            return Err(Box::new(ContentLengthMismatch {
+                expected: len,
+                actual,
+            }));
+        }
+    }
 
-    // We use unwrap here becuase hasher is guaranteed
-    // to return a 20-byte slice.
-    Ok(Id::new(id).unwrap())
+    let final_hash: [u8; 20] = hasher.finalize().into();
+    Ok(Id::from(final_hash))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{fs::File, io::Write, process::Command};
+    use std::{
+        cell::Cell, collections::hash_map::DefaultHasher, fs::File, io::Write, process::Command,
+        rc::Rc,
+    };
 
     use super::*;
 
@@ -213,6 +317,29 @@ mod tests {
         assert_eq!(buf, [67, 3, 45]);
     }
 
+    #[test]
+    fn borrowed_slice() {
+        let content = b"test content\n".to_vec();
+
+        let borrowed = Object::new(&Kind::Blob, Box::new(&content[..])).unwrap();
+        let owned = Object::new(&Kind::Blob, Box::new(content.clone())).unwrap();
+
+        assert_eq!(borrowed.id(), owned.id());
+        assert_eq!(
+            borrowed.id().to_string(),
+            "d670460b4b4aece5915caf5c68d12f560a9fe3e4"
+        );
+        assert_eq!(borrowed.len(), 13);
+
+        let mut read_back = Vec::new();
+        borrowed
+            .open()
+            .unwrap()
+            .read_to_end(&mut read_back)
+            .unwrap();
+        assert_eq!(read_back, content);
+    }
+
     #[test]
     fn empty_str() {
         let s = "".to_string();
@@ -344,6 +471,202 @@ mod tests {
         assert_eq!(o.id().to_string(), expected_id);
     }
 
+    #[test]
+    fn blob_constructor() {
+        let o = Object::blob(Box::new("test content\n".to_string())).unwrap();
+        assert_eq!(o.kind(), &Kind::Blob);
+        assert_eq!(o.len(), 13);
+        assert_eq!(
+            o.id().to_string(),
+            "d670460b4b4aece5915caf5c68d12f560a9fe3e4"
+        );
+    }
+
+    #[test]
+    fn commit_constructor() {
+        let content = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+                       author A U Thor <author@example.com> 1112911993 -0700\n\
+                       committer C O Mitter <committer@example.com> 1112911993 -0700\n\
+                       \n\
+                       initial\n";
+        let o = Object::commit(Box::new(content.to_string())).unwrap();
+        assert_eq!(o.kind(), &Kind::Commit);
+        assert_eq!(o.len(), 171);
+        assert_eq!(
+            o.id().to_string(),
+            "66fe8b3f2df5c2a6e67944af865f3a0893093d69"
+        );
+    }
+
+    #[test]
+    fn tree_constructor() {
+        let o = Object::tree(Box::new(Vec::new())).unwrap();
+        assert_eq!(o.kind(), &Kind::Tree);
+        assert_eq!(o.len(), 0);
+        assert_eq!(
+            o.id().to_string(),
+            "4b825dc642cb6eb9a060e54bf8d69288fbee4904"
+        );
+    }
+
+    #[test]
+    fn tag_constructor() {
+        let content = "object 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+                       type tree\n\
+                       tag v1\n\
+                       tagger C O Mitter <committer@example.com> 1112911993 -0700\n\
+                       \n\
+                       message\n";
+        let o = Object::tag(Box::new(content.to_string())).unwrap();
+        assert_eq!(o.kind(), &Kind::Tag);
+        assert_eq!(o.len(), 133);
+        assert_eq!(
+            o.id().to_string(),
+            "7ee657f9d539c6d0ae1bea8b214c670eb340a2f8"
+        );
+    }
+
+    struct CountingContentSource {
+        inner: Box<dyn ContentSource>,
+        opens: Rc<Cell<usize>>,
+    }
+
+    impl ContentSource for CountingContentSource {
+        fn len(&self) -> u64 {
+            self.inner.len()
+        }
+
+        fn open(&self) -> ContentSourceOpenResult {
+            self.opens.set(self.opens.get() + 1);
+            self.inner.open()
+        }
+    }
+
+    #[test]
+    fn len_doesnt_open_content() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.as_ref().join("example");
+        std::fs::write(&path, b"test content\n").unwrap();
+
+        let sources: Vec<Box<dyn ContentSource>> = vec![
+            Box::new(b"test content\n".to_vec()),
+            Box::new("test content\n".to_string()),
+            Box::new(FileContentSource::new(&path).unwrap()),
+            Box::new(ReadContentSource::new(&mut &b"test content\n"[..]).unwrap()),
+        ];
+
+        for inner in sources {
+            let opens = Rc::new(Cell::new(0));
+            let cs = CountingContentSource {
+                inner,
+                opens: opens.clone(),
+            };
+
+            let o = Object::new(&Kind::Blob, Box::new(cs)).unwrap();
+            let opens_after_new = opens.get();
+
+            assert_eq!(o.len(), 13);
+            assert!(!o.is_empty());
+            assert_eq!(o.len(), 13);
+
+            assert_eq!(opens.get(), opens_after_new);
+        }
+    }
+
+    #[test]
+    fn header_fn() {
+        let o = Object::blob(Box::new("test content\n".to_string())).unwrap();
+        assert_eq!(o.header(), b"blob 13\0");
+
+        let o = Object::new(&Kind::Tree, Box::new(Vec::new())).unwrap();
+        assert_eq!(o.header(), b"tree 0\0");
+    }
+
+    #[test]
+    fn header_larger_than_u32() {
+        let len = u64::from(u32::MAX) + 2;
+        assert_eq!(header(&Kind::Blob, len), b"blob 4294967297\0");
+
+        // A source claiming to be that large (but which isn't) shouldn't
+        // be truncated when reporting the mismatch.
+        let cs = LyingContentSource {
+            reported_len: len,
+            content: b"test content\n".to_vec(),
+        };
+        let err = Object::new(&Kind::Blob, Box::new(cs)).err().unwrap();
+        assert_eq!(
+            err.downcast_ref::<ContentLengthMismatch>().unwrap(),
+            &ContentLengthMismatch {
+                expected: 4_294_967_297,
+                actual: 13
+            }
+        );
+    }
+
+    struct LyingContentSource {
+        reported_len: u64,
+        content: Vec<u8>,
+    }
+
+    impl ContentSource for LyingContentSource {
+        fn len(&self) -> u64 {
+            self.reported_len
+        }
+
+        fn open(&self) -> ContentSourceOpenResult {
+            self.content.open()
+        }
+    }
+
+    #[test]
+    fn error_content_length_mismatch() {
+        let cs = LyingContentSource {
+            reported_len: 10,
+            content: b"test content\n".to_vec(),
+        };
+        let err = Object::new(&Kind::Blob, Box::new(cs)).err().unwrap();
+
+        assert_eq!(
+            err.downcast_ref::<ContentLengthMismatch>().unwrap(),
+            &ContentLengthMismatch {
+                expected: 10,
+                actual: 13
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "content source reported 10 bytes, but provided 13 bytes"
+        );
+
+        let cs = LyingContentSource {
+            reported_len: 20,
+            content: b"test content\n".to_vec(),
+        };
+        let err = Object::new(&Kind::Blob, Box::new(cs)).err().unwrap();
+        assert!(err.downcast_ref::<ContentLengthMismatch>().is_some());
+    }
+
+    fn hash_of(o: &Object) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        o.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn eq_and_hash_by_id() {
+        let o1 = Object::new(&Kind::Blob, Box::new("test content\n".to_string())).unwrap();
+        let o2 = Object::new(&Kind::Blob, Box::new(b"test content\n".to_vec())).unwrap();
+        assert!(o1 == o2);
+        assert_eq!(hash_of(&o1), hash_of(&o2));
+
+        let o3 = Object::new(&Kind::Blob, Box::new("other content\n".to_string())).unwrap();
+        assert!(o1 != o3);
+        assert_ne!(hash_of(&o1), hash_of(&o3));
+
+        let o4 = Object::new(&Kind::Commit, Box::new("test content\n".to_string())).unwrap();
+        assert!(o1 != o4);
+    }
+
     #[test]
     fn check_blob_valid() {
         let cs = "no such thing as an invalid blob".to_string();
@@ -449,7 +772,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: true
+                mac: true,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             true
@@ -467,7 +791,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: true
+                mac: true,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             true
@@ -485,7 +810,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: true
+                mac: true,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             true
@@ -503,7 +829,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: true
+                mac: true,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             false
@@ -522,7 +849,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: true
+                mac: true,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             true
@@ -537,7 +865,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: true
+                mac: true,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             false
@@ -552,7 +881,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: false
+                mac: false,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             true
@@ -570,7 +900,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: false,
-                mac: false
+                mac: false,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             false
@@ -585,7 +916,8 @@ mod tests {
         assert_eq!(
             o.is_valid_with_platform_checks(&CheckPlatforms {
                 windows: true,
-                mac: false
+                mac: false,
+                ntfs_segment_length: false,
             })
             .unwrap(),
             false
@@ -1,12 +1,29 @@
-use std::io::{BufRead, Result};
+use std::io::{BufRead, Error, ErrorKind, Read, Result};
+
+// Longest header line (not including the newline) that we'll read
+// when validating a commit or tag. Legitimate header lines are far
+// shorter; this keeps a corrupt or malicious object without newlines
+// from being read into memory in its entirety.
+pub(crate) const MAX_LINE_LEN: usize = 64 * 1024;
 
 // Read one line from input source if possible.
 pub(crate) fn read_line<B: BufRead>(b: &mut B) -> Result<Option<Vec<u8>>> {
+    read_line_bounded(b, MAX_LINE_LEN)
+}
+
+// Read one line from input source if possible. Returns an error
+// if the line (excluding the newline) is longer than `max_len` bytes.
+pub(crate) fn read_line_bounded<B: BufRead>(b: &mut B, max_len: usize) -> Result<Option<Vec<u8>>> {
     let mut line = Vec::new();
 
-    if b.read_until(10, &mut line)? > 0 {
+    if b.take(max_len as u64 + 1).read_until(10, &mut line)? > 0 {
         if let Some(10) = line.last() {
             line.truncate(line.len() - 1);
+        } else if line.len() > max_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("line exceeds {} byte limit", max_len),
+            ));
         }
         Ok(Some(line))
     } else {
@@ -146,6 +163,25 @@ mod tests {
         assert!(read_line(&mut c).unwrap().is_none());
     }
 
+    #[test]
+    fn read_line_bounded_fn() {
+        let mut c = Cursor::new(&b"abc\ndefg\n");
+
+        let line = read_line_bounded(&mut c, 3).unwrap().unwrap();
+        assert_eq!(line.as_slice(), b"abc");
+
+        let err = read_line_bounded(&mut c, 3).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line exceeds 3 byte limit");
+
+        let mut c = Cursor::new(&b"abc");
+        let line = read_line_bounded(&mut c, 3).unwrap().unwrap();
+        assert_eq!(line.as_slice(), b"abc");
+
+        let mut c = Cursor::new(&b"abcd");
+        assert!(read_line_bounded(&mut c, 3).is_err());
+    }
+
     #[test]
     fn header_fn() {
         assert_eq!(header(b"tagger abc", b"tagger").unwrap(), b"abc");
@@ -0,0 +1,93 @@
+use std::io::{self, BufRead, Read};
+
+/// Wraps a [`BufRead`] and counts the bytes read through it, so that
+/// callers reading large objects can report progress.
+///
+/// Obtain one via [`Object::open_with_progress()`]. Plain [`Object::open()`]
+/// doesn't count, so there's no overhead unless progress is wanted.
+///
+/// [`BufRead`]: https://doc.rust-lang.org/nightly/std/io/trait.BufRead.html
+/// [`Object::open()`]: struct.Object.html#method.open
+/// [`Object::open_with_progress()`]: struct.Object.html#method.open_with_progress
+pub struct ProgressReader<R> {
+    inner: R,
+    bytes_read: u64,
+}
+
+impl<R> ProgressReader<R> {
+    /// Wrap `inner`, starting the count at zero.
+    pub fn new(inner: R) -> ProgressReader<R> {
+        ProgressReader {
+            inner,
+            bytes_read: 0,
+        }
+    }
+
+    /// Returns the number of bytes read (or consumed) so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for ProgressReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.bytes_read += amt as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Read};
+
+    use crate::object::{Kind, Object};
+
+    #[test]
+    fn counts_to_len() {
+        let content = vec![b'x'; 10_000];
+        let o = Object::new(&Kind::Blob, Box::new(content)).unwrap();
+
+        let mut r = o.open_with_progress().unwrap();
+        assert_eq!(r.bytes_read(), 0);
+
+        let mut buf = [0u8; 300];
+        let n = r.read(&mut buf).unwrap();
+        assert_eq!(r.bytes_read(), n as u64);
+
+        let mut rest = Vec::new();
+        r.read_to_end(&mut rest).unwrap();
+        assert_eq!(r.bytes_read(), o.len());
+    }
+
+    #[test]
+    fn counts_consume() {
+        let o = Object::new(&Kind::Blob, Box::new(b"abc\ndef\n".to_vec())).unwrap();
+
+        let mut r = o.open_with_progress().unwrap();
+        let mut line = String::new();
+        r.read_line(&mut line).unwrap();
+        assert_eq!(line, "abc\n");
+        assert_eq!(r.bytes_read(), 4);
+
+        let len = r.fill_buf().unwrap().len();
+        r.consume(len);
+        assert_eq!(r.bytes_read(), o.len());
+    }
+}
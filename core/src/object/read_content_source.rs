@@ -42,8 +42,8 @@ impl ReadContentSource {
 }
 
 impl ContentSource for ReadContentSource {
-    fn len(&self) -> usize {
-        self.content.len()
+    fn len(&self) -> u64 {
+        self.content.len() as u64
     }
 
     fn open(&self) -> ContentSourceOpenResult {
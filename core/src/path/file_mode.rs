@@ -8,6 +8,12 @@
 /// * `0o120000` - symbolic link
 /// * `0o040000` - tree (subdirectory)
 /// * `0o160000` - submodule (aka gitlink)
+///
+/// Some early versions of git wrote group-writable files as `0o100664`.
+/// Like command-line git (outside of `fsck --strict`), we accept that
+/// value and treat it as a normal file; it converts back to the canonical
+/// `0o100644`. Other non-canonical file permissions (e.g. `0o100640`)
+/// are rejected.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum FileMode {
     Normal,
@@ -26,6 +32,7 @@ impl FileMode {
         // There are so few values, why bother actually parsing the octal?
         match value {
             b"100644" => Some(FileMode::Normal),
+            b"100664" => Some(FileMode::Normal),
             b"100755" => Some(FileMode::Executable),
             b"120000" => Some(FileMode::SymbolicLink),
             b"40000" => Some(FileMode::Tree),
@@ -41,6 +48,7 @@ impl FileMode {
     pub fn from_value(value: u32) -> Option<FileMode> {
         match value {
             0o100644 => Some(FileMode::Normal),
+            0o100664 => Some(FileMode::Normal),
             0o100755 => Some(FileMode::Executable),
             0o120000 => Some(FileMode::SymbolicLink),
             0o040000 => Some(FileMode::Tree),
@@ -96,6 +104,20 @@ mod tests {
         assert!(FileMode::from_octal_slice(b"100643").is_none());
     }
 
+    #[test]
+    fn from_octal_slice_group_writable() {
+        assert_eq!(
+            FileMode::from_octal_slice(b"100664").unwrap(),
+            FileMode::Normal
+        );
+        assert_eq!(
+            FileMode::from_octal_slice(b"100664").unwrap().to_value(),
+            0o100644
+        );
+        assert!(FileMode::from_octal_slice(b"100640").is_none());
+        assert!(FileMode::from_octal_slice(b"100666").is_none());
+    }
+
     #[test]
     fn from_value() {
         assert_eq!(FileMode::from_value(0o100644).unwrap(), FileMode::Normal);
@@ -114,6 +136,14 @@ mod tests {
         assert!(FileMode::from_value(0x100643).is_none());
     }
 
+    #[test]
+    fn from_value_group_writable() {
+        assert_eq!(FileMode::from_value(0o100664).unwrap(), FileMode::Normal);
+        assert_eq!(FileMode::from_value(0o100664).unwrap().to_value(), 0o100644);
+        assert!(FileMode::from_value(0o100640).is_none());
+        assert!(FileMode::from_value(0o100666).is_none());
+    }
+
     #[test]
     fn to_value() {
         assert_eq!(FileMode::to_value(FileMode::Normal), 0o100644);
@@ -11,6 +11,9 @@ pub use file_mode::FileMode;
 mod path_mode;
 pub use path_mode::PathMode;
 
+mod quote;
+pub use quote::quote_c_style;
+
 /// Represents a sequence of bytes that is a valid path in a git repo.
 ///
 /// A path is typically, but not necessarily, interpreted as UTF-8.
@@ -64,6 +67,9 @@ pub enum PathError {
     #[error("the name `{}` is a reserved device name on Windows", String::from_utf8_lossy(.0))]
     ReservedWindowsDeviceName(Vec<u8>),
 
+    #[error("the path segment is {0} UTF-16 code units long, which exceeds the NTFS limit of 255")]
+    SegmentTooLong(usize),
+
     #[error("the name contains Unicode characters which are ignorable")]
     ContainsIgnorableUnicodeCharacters,
 
@@ -76,6 +82,11 @@ pub enum PathError {
 pub struct CheckPlatforms {
     pub windows: bool,
     pub mac: bool,
+
+    /// Reject path segments longer than 255 UTF-16 code units, which is
+    /// the longest file name that NTFS allows. Command-line git doesn't
+    /// check this, so it's independent of `windows`.
+    pub ntfs_segment_length: bool,
 }
 
 impl<'a> Path<'a> {
@@ -92,6 +103,7 @@ impl<'a> Path<'a> {
             &CheckPlatforms {
                 windows: false,
                 mac: false,
+                ntfs_segment_length: false,
             },
         )
     }
@@ -108,7 +120,7 @@ impl<'a> Path<'a> {
     ) -> Result<Path<'a>, PathError> {
         // Argh. `cargo fmt` reformats this into a format that generates
         // "coverage" for some of the arguments below, but not all.
-        match check_path(path, platforms) {
+        match validate_path(path, platforms) {
             Ok(()) => Ok(Path {
                 path,
                 checked_platforms: platforms.clone(),
@@ -141,6 +153,7 @@ impl<'a> PathSegment<'a> {
             &CheckPlatforms {
                 windows: false,
                 mac: false,
+                ntfs_segment_length: false,
             },
         )
     }
@@ -177,7 +190,15 @@ impl<'a> PathSegment<'a> {
     }
 }
 
-fn check_path(path: &[u8], platforms: &CheckPlatforms) -> Result<(), PathError> {
+/// Check that the provided byte sequence is acceptable as a git path,
+/// including any platform-specific rules requested via `platforms`.
+///
+/// This is the same check performed by [`Path::new_with_platform_checks()`],
+/// without constructing a `Path`. It accepts arbitrary input (including
+/// bytes that are not valid UTF-8) and never panics.
+///
+/// [`Path::new_with_platform_checks()`]: struct.Path.html#method.new_with_platform_checks
+pub fn validate_path(path: &[u8], platforms: &CheckPlatforms) -> Result<(), PathError> {
     if path.is_empty() {
         Err(PathError::EmptyPath)
     } else if path.starts_with(b"/") {
@@ -212,6 +233,10 @@ fn check_segment(segment: &[u8], platforms: &CheckPlatforms) -> Result<(), PathE
             check_windows_device_name(segment)?;
         }
 
+        if platforms.ntfs_segment_length {
+            check_ntfs_segment_length(segment)?;
+        }
+
         if platforms.mac {
             check_git_path_with_mac_ignorables(segment)?;
             check_truncated_utf8_for_mac(segment)?
@@ -316,6 +341,17 @@ fn check_windows_segment_ending(segment: &[u8]) -> Result<(), PathError> {
     }
 }
 
+fn check_ntfs_segment_length(segment: &[u8]) -> Result<(), PathError> {
+    // NTFS limits each path component to 255 UTF-16 code units.
+    // Any invalid UTF-8 counts as one unit per replacement character.
+    let len = String::from_utf8_lossy(segment).encode_utf16().count();
+    if len > 255 {
+        Err(PathError::SegmentTooLong(len))
+    } else {
+        Ok(())
+    }
+}
+
 #[cfg(not(tarpaulin_include))]
 fn check_windows_device_name(segment: &[u8]) -> Result<(), PathError> {
     // Coverage skip justification: We have to cover the `None` case,
@@ -370,83 +406,90 @@ fn check_git_path_with_mac_ignorables(segment: &[u8]) -> Result<(), PathError> {
 }
 
 fn check_truncated_utf8_for_mac(segment: &[u8]) -> Result<(), PathError> {
-    let tail3 = &segment[2.max(segment.len()) - 2..];
-    if tail3.contains(&0xE2) || tail3.contains(&0xEF) {
+    // Each of the ignorable characters is three bytes long and begins
+    // with 0xE2 or 0xEF. If one of those lead bytes appears in the last
+    // two bytes, the character has been truncated.
+    let tail2 = &segment[segment.len().saturating_sub(2)..];
+    if tail2.contains(&0xE2) || tail2.contains(&0xEF) {
         Err(PathError::ContainsIncompleteUnicodeCharacters)
     } else {
         Ok(())
     }
 }
 
-fn match_mac_hfs_path(segment: &[u8], m: &[u8]) -> bool {
-    if segment.is_empty() && m.is_empty() {
-        true
-    } else if segment.is_empty() {
-        false
-    } else {
-        if segment.len() >= 3 {
-            let ignorable_char = match segment[0..3] {
-                // U+200C 0xe2808c ZERO WIDTH NON-JOINER
-                [0xE2, 0x80, 0x8C] => true,
+fn match_mac_hfs_path(mut segment: &[u8], mut m: &[u8]) -> bool {
+    // This is written as a loop rather than recursively so that a long
+    // run of ignorable characters can't exhaust the stack.
+    loop {
+        if segment.is_empty() {
+            return m.is_empty();
+        }
 
-                // U+200D 0xe2808d ZERO WIDTH JOINER
-                [0xE2, 0x80, 0x8D] => true,
+        if segment.len() >= 3 && is_mac_ignorable(&segment[0..3]) {
+            segment = &segment[3..];
+            continue;
+        }
 
-                // U+200E 0xe2808e LEFT-TO-RIGHT MARK
-                [0xE2, 0x80, 0x8E] => true,
+        if m.is_empty() || segment[0] != m[0] {
+            return false;
+        }
 
-                // U+200F 0xe2808f RIGHT-TO-LEFT MARK
-                [0xE2, 0x80, 0x8F] => true,
+        segment = &segment[1..];
+        m = &m[1..];
+    }
+}
 
-                // U+202A 0xe280aa LEFT-TO-RIGHT EMBEDDING
-                [0xE2, 0x80, 0xAA] => true,
+fn is_mac_ignorable(c: &[u8]) -> bool {
+    match c {
+        // U+200C 0xe2808c ZERO WIDTH NON-JOINER
+        [0xE2, 0x80, 0x8C] => true,
 
-                // U+202B 0xe280ab RIGHT-TO-LEFT EMBEDDING
-                [0xE2, 0x80, 0xAB] => true,
+        // U+200D 0xe2808d ZERO WIDTH JOINER
+        [0xE2, 0x80, 0x8D] => true,
 
-                // U+202C 0xe280ac POP DIRECTIONAL FORMATTING
-                [0xE2, 0x80, 0xAC] => true,
+        // U+200E 0xe2808e LEFT-TO-RIGHT MARK
+        [0xE2, 0x80, 0x8E] => true,
 
-                // U+202D 0xe280ad LEFT-TO-RIGHT OVERRIDE
-                [0xE2, 0x80, 0xAD] => true,
+        // U+200F 0xe2808f RIGHT-TO-LEFT MARK
+        [0xE2, 0x80, 0x8F] => true,
 
-                // U+202E 0xe280ae RIGHT-TO-LEFT OVERRIDE
-                [0xE2, 0x80, 0xAE] => true,
+        // U+202A 0xe280aa LEFT-TO-RIGHT EMBEDDING
+        [0xE2, 0x80, 0xAA] => true,
 
-                // U+206A 0xe281aa INHIBIT SYMMETRIC SWAPPING
-                [0xE2, 0x81, 0xAA] => true,
+        // U+202B 0xe280ab RIGHT-TO-LEFT EMBEDDING
+        [0xE2, 0x80, 0xAB] => true,
 
-                // U+206B 0xe281ab ACTIVATE SYMMETRIC SWAPPING
-                [0xE2, 0x81, 0xAB] => true,
+        // U+202C 0xe280ac POP DIRECTIONAL FORMATTING
+        [0xE2, 0x80, 0xAC] => true,
 
-                // U+206C 0xe281ac INHIBIT ARABIC FORM SHAPING
-                [0xE2, 0x81, 0xAC] => true,
+        // U+202D 0xe280ad LEFT-TO-RIGHT OVERRIDE
+        [0xE2, 0x80, 0xAD] => true,
 
-                // U+206D 0xe281ad ACTIVATE ARABIC FORM SHAPING
-                [0xE2, 0x81, 0xAD] => true,
+        // U+202E 0xe280ae RIGHT-TO-LEFT OVERRIDE
+        [0xE2, 0x80, 0xAE] => true,
 
-                // U+206E 0xe281ae NATIONAL DIGIT SHAPES
-                [0xE2, 0x81, 0xAE] => true,
+        // U+206A 0xe281aa INHIBIT SYMMETRIC SWAPPING
+        [0xE2, 0x81, 0xAA] => true,
 
-                // U+206F 0xe281af NOMINAL DIGIT SHAPES
-                [0xE2, 0x81, 0xAF] => true,
+        // U+206B 0xe281ab ACTIVATE SYMMETRIC SWAPPING
+        [0xE2, 0x81, 0xAB] => true,
 
-                // U+FEFF 0xefbbbf BYTE ORDER MARK
-                [0xEF, 0xBB, 0xBF] => true,
+        // U+206C 0xe281ac INHIBIT ARABIC FORM SHAPING
+        [0xE2, 0x81, 0xAC] => true,
 
-                _ => false,
-            };
+        // U+206D 0xe281ad ACTIVATE ARABIC FORM SHAPING
+        [0xE2, 0x81, 0xAD] => true,
 
-            if ignorable_char {
-                return match_mac_hfs_path(&segment[3..], m);
-            }
-        }
+        // U+206E 0xe281ae NATIONAL DIGIT SHAPES
+        [0xE2, 0x81, 0xAE] => true,
 
-        if m.is_empty() || segment.first() != m.first() {
-            false
-        } else {
-            match_mac_hfs_path(&segment[1..], &m[1..])
-        }
+        // U+206F 0xe281af NOMINAL DIGIT SHAPES
+        [0xE2, 0x81, 0xAF] => true,
+
+        // U+FEFF 0xefbbbf BYTE ORDER MARK
+        [0xEF, 0xBB, 0xBF] => true,
+
+        _ => false,
     }
 }
 
@@ -465,7 +508,8 @@ mod path_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: false,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -572,7 +616,8 @@ mod path_tests {
                     name,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -596,7 +641,8 @@ mod path_tests {
                     &name,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -609,6 +655,7 @@ mod path_tests {
             &CheckPlatforms {
                 windows: true,
                 mac: false,
+                ntfs_segment_length: false,
             },
         )
         .unwrap();
@@ -617,7 +664,8 @@ mod path_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: false,
-                windows: true
+                windows: true,
+                ntfs_segment_length: false
             }
         )
     }
@@ -633,7 +681,8 @@ mod path_tests {
                 name,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -649,7 +698,8 @@ mod path_tests {
                 name,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -675,7 +725,8 @@ mod path_tests {
                     name,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -692,6 +743,7 @@ mod path_tests {
                 &CheckPlatforms {
                     windows: true,
                     mac: false,
+                    ntfs_segment_length: false,
                 },
             )
             .unwrap();
@@ -700,6 +752,68 @@ mod path_tests {
         }
     }
 
+    #[test]
+    fn ntfs_segment_too_long() {
+        let ntfs = CheckPlatforms {
+            windows: false,
+            mac: false,
+            ntfs_segment_length: true,
+        };
+
+        let path = format!("{}/{}", "a".repeat(300), "b".repeat(255));
+        assert!(Path::new(path.as_bytes()).is_ok());
+        assert!(Path::new_with_platform_checks(
+            path.as_bytes(),
+            &CheckPlatforms {
+                windows: true,
+                mac: false,
+                ntfs_segment_length: false
+            }
+        )
+        .is_ok());
+        assert_eq!(
+            Path::new_with_platform_checks(path.as_bytes(), &ntfs).unwrap_err(),
+            PathError::SegmentTooLong(300)
+        );
+
+        let path = format!("{}/{}", "a".repeat(255), "b".repeat(255));
+        let a = Path::new_with_platform_checks(path.as_bytes(), &ntfs).unwrap();
+        assert_eq!(a.path(), path.as_bytes());
+
+        assert_eq!(
+            PathError::SegmentTooLong(256).to_string(),
+            "the path segment is 256 UTF-16 code units long, which exceeds the NTFS limit of 255"
+        );
+    }
+
+    #[test]
+    fn ntfs_segment_length_multi_byte() {
+        let ntfs = CheckPlatforms {
+            windows: false,
+            mac: false,
+            ntfs_segment_length: true,
+        };
+
+        // 100 CJK characters are 300 bytes, but only 100 UTF-16 code units.
+        let path = format!("dir/{}", "\u{6587}".repeat(100));
+        assert_eq!(path.len(), 304);
+        let a = Path::new_with_platform_checks(path.as_bytes(), &ntfs).unwrap();
+        assert_eq!(a.path(), path.as_bytes());
+
+        let path = format!("dir/{}", "\u{6587}".repeat(256));
+        assert_eq!(
+            Path::new_with_platform_checks(path.as_bytes(), &ntfs).unwrap_err(),
+            PathError::SegmentTooLong(256)
+        );
+
+        // Characters outside the BMP take two UTF-16 code units each.
+        let path = format!("dir/{}", "\u{1F600}".repeat(128));
+        assert_eq!(
+            Path::new_with_platform_checks(path.as_bytes(), &ntfs).unwrap_err(),
+            PathError::SegmentTooLong(256)
+        );
+    }
+
     const MAC_HFS_GIT_NAMES: [&str; 16] = [
         ".gi\u{200C}t",
         ".gi\u{200D}t",
@@ -734,7 +848,8 @@ mod path_tests {
                     name,
                     &CheckPlatforms {
                         windows: false,
-                        mac: true
+                        mac: true,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -753,6 +868,7 @@ mod path_tests {
                 &CheckPlatforms {
                     windows: false,
                     mac: true,
+                    ntfs_segment_length: false,
                 },
             )
             .unwrap();
@@ -761,7 +877,8 @@ mod path_tests {
                 a.checked_platforms(),
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
         }
@@ -774,7 +891,8 @@ mod path_tests {
                 &[97, 98, 0xE2, 0x80],
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -786,7 +904,8 @@ mod path_tests {
                 &[97, 98, 0xEF, 0x80],
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -799,6 +918,7 @@ mod path_tests {
             &CheckPlatforms {
                 windows: false,
                 mac: true,
+                ntfs_segment_length: false,
             },
         )
         .unwrap();
@@ -808,7 +928,8 @@ mod path_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: true,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -820,7 +941,8 @@ mod path_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: false,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -829,7 +951,8 @@ mod path_tests {
                 bad_name,
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -844,7 +967,8 @@ mod path_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: false,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -853,7 +977,8 @@ mod path_tests {
                 bad_name,
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -877,7 +1002,8 @@ mod path_segment_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: false,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -990,7 +1116,8 @@ mod path_segment_tests {
                     name,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -1014,7 +1141,8 @@ mod path_segment_tests {
                     &name,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -1034,7 +1162,8 @@ mod path_segment_tests {
                 name,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -1050,7 +1179,8 @@ mod path_segment_tests {
                 name,
                 &CheckPlatforms {
                     windows: true,
-                    mac: false
+                    mac: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -1076,7 +1206,8 @@ mod path_segment_tests {
                     name,
                     &CheckPlatforms {
                         windows: true,
-                        mac: false
+                        mac: false,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -1093,6 +1224,7 @@ mod path_segment_tests {
                 &CheckPlatforms {
                     windows: true,
                     mac: false,
+                    ntfs_segment_length: false,
                 },
             )
             .unwrap();
@@ -1101,6 +1233,40 @@ mod path_segment_tests {
         }
     }
 
+    #[test]
+    fn ntfs_segment_too_long() {
+        let ntfs = CheckPlatforms {
+            windows: false,
+            mac: false,
+            ntfs_segment_length: true,
+        };
+
+        let name = "a".repeat(255);
+        let a = PathSegment::new_with_platform_checks(name.as_bytes(), &ntfs).unwrap();
+        assert_eq!(a.path(), name.as_bytes());
+
+        let name = "a".repeat(256);
+        assert!(PathSegment::new(name.as_bytes()).is_ok());
+        assert!(PathSegment::new_with_platform_checks(
+            name.as_bytes(),
+            &CheckPlatforms {
+                windows: true,
+                mac: false,
+                ntfs_segment_length: false
+            }
+        )
+        .is_ok());
+        assert_eq!(
+            PathSegment::new_with_platform_checks(name.as_bytes(), &ntfs).unwrap_err(),
+            PathError::SegmentTooLong(256)
+        );
+
+        let name = "\u{e9}".repeat(255);
+        assert_eq!(name.len(), 510);
+        let a = PathSegment::new_with_platform_checks(name.as_bytes(), &ntfs).unwrap();
+        assert_eq!(a.path(), name.as_bytes());
+    }
+
     const MAC_HFS_GIT_NAMES: [&str; 16] = [
         ".gi\u{200C}t",
         ".gi\u{200D}t",
@@ -1135,7 +1301,8 @@ mod path_segment_tests {
                     name,
                     &CheckPlatforms {
                         windows: false,
-                        mac: true
+                        mac: true,
+                        ntfs_segment_length: false
                     }
                 )
                 .unwrap_err(),
@@ -1154,6 +1321,7 @@ mod path_segment_tests {
                 &CheckPlatforms {
                     windows: false,
                     mac: true,
+                    ntfs_segment_length: false,
                 },
             )
             .unwrap();
@@ -1162,7 +1330,8 @@ mod path_segment_tests {
                 a.checked_platforms(),
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
         }
@@ -1175,7 +1344,8 @@ mod path_segment_tests {
                 &[97, 98, 0xE2, 0x80],
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -1187,7 +1357,8 @@ mod path_segment_tests {
                 &[97, 98, 0xEF, 0x80],
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -1200,6 +1371,7 @@ mod path_segment_tests {
             &CheckPlatforms {
                 windows: false,
                 mac: true,
+                ntfs_segment_length: false,
             },
         )
         .unwrap();
@@ -1209,7 +1381,8 @@ mod path_segment_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: true,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -1221,7 +1394,8 @@ mod path_segment_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: false,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -1230,7 +1404,8 @@ mod path_segment_tests {
                 bad_name,
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -1245,7 +1420,8 @@ mod path_segment_tests {
             a.checked_platforms(),
             &CheckPlatforms {
                 mac: false,
-                windows: false
+                windows: false,
+                ntfs_segment_length: false
             }
         );
 
@@ -1254,7 +1430,8 @@ mod path_segment_tests {
                 bad_name,
                 &CheckPlatforms {
                     mac: true,
-                    windows: false
+                    windows: false,
+                    ntfs_segment_length: false
                 }
             )
             .unwrap_err(),
@@ -1262,3 +1439,104 @@ mod path_segment_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod validate_path_tests {
+    use super::*;
+
+    use quickcheck::quickcheck;
+
+    const ALL_PLATFORMS: [CheckPlatforms; 4] = [
+        CheckPlatforms {
+            windows: false,
+            mac: false,
+            ntfs_segment_length: false,
+        },
+        CheckPlatforms {
+            windows: true,
+            mac: false,
+            ntfs_segment_length: false,
+        },
+        CheckPlatforms {
+            windows: false,
+            mac: true,
+            ntfs_segment_length: false,
+        },
+        CheckPlatforms {
+            windows: true,
+            mac: true,
+            ntfs_segment_length: false,
+        },
+    ];
+
+    fn check_all_platforms(path: &[u8]) {
+        for platforms in ALL_PLATFORMS.iter() {
+            let result = validate_path(path, platforms);
+            assert_eq!(
+                result.is_ok(),
+                Path::new_with_platform_checks(path, platforms).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        check_all_platforms(b"");
+        check_all_platforms(b"/");
+        check_all_platforms(b"//");
+        check_all_platforms(b".");
+        check_all_platforms(b"\0");
+        check_all_platforms(b"a\0b/c");
+        check_all_platforms(b"\xE2");
+        check_all_platforms(b"\xEF");
+        check_all_platforms(b"\xFF");
+        check_all_platforms(b"\xE2\x80");
+        check_all_platforms(b"a/\xE2\x80");
+        check_all_platforms(b"com");
+        check_all_platforms(b"com1.");
+        check_all_platforms(b".git\xE2\x80\x8C/x");
+
+        assert_eq!(
+            validate_path(b"", &ALL_PLATFORMS[3]).unwrap_err(),
+            PathError::EmptyPath
+        );
+        assert_eq!(
+            validate_path(b"\xE2", &ALL_PLATFORMS[2]).unwrap_err(),
+            PathError::ContainsIncompleteUnicodeCharacters
+        );
+        assert!(validate_path(b"\xFF", &ALL_PLATFORMS[3]).is_ok());
+    }
+
+    #[test]
+    fn long_run_of_mac_ignorables() {
+        let mut path = b".".to_vec();
+        for _ in 0..1_000_000 {
+            path.extend_from_slice(b"\xE2\x80\x8C");
+        }
+        path.extend_from_slice(b"git");
+
+        assert_eq!(
+            validate_path(&path, &ALL_PLATFORMS[2]).unwrap_err(),
+            PathError::ContainsIgnorableUnicodeCharacters
+        );
+    }
+
+    quickcheck! {
+        fn never_panics(path: Vec<u8>) -> bool {
+            check_all_platforms(&path);
+            true
+        }
+
+        fn never_panics_short_segments(segments: Vec<Vec<u8>>) -> bool {
+            // Random byte vectors rarely contain '/', so also build
+            // multi-segment paths from short random pieces.
+            let path = segments
+                .iter()
+                .map(|s| &s[..s.len().min(6)])
+                .collect::<Vec<&[u8]>>()
+                .join(&b'/');
+            check_all_platforms(&path);
+            true
+        }
+    }
+}
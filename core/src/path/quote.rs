@@ -0,0 +1,77 @@
+/// Render a path for human-readable (non-`-z`) output in the same way as
+/// command-line git with its default `core.quotePath = true` setting.
+///
+/// If the path contains no special bytes, it is returned unchanged.
+/// Otherwise, the path is enclosed in double quotes and the following
+/// bytes are escaped:
+///
+/// * `"` and `\` are preceded by a backslash
+/// * control characters with a C escape sequence (e.g. newline) are
+///   rendered as that escape sequence (`\n`)
+/// * other control characters, `DEL`, and all bytes `0x80` and above
+///   (i.e. any non-ASCII UTF-8) are rendered as three-digit octal
+///   escapes (`\303\251`)
+pub fn quote_c_style(path: &[u8]) -> String {
+    if !path.iter().any(|c| needs_quote(*c)) {
+        // No special bytes implies the path is pure ASCII.
+        return String::from_utf8_lossy(path).into_owned();
+    }
+
+    let mut result = String::with_capacity(path.len() + 2);
+    result.push('"');
+
+    for &c in path {
+        match c {
+            b'"' => result.push_str("\\\""),
+            b'\\' => result.push_str("\\\\"),
+            0x07 => result.push_str("\\a"),
+            0x08 => result.push_str("\\b"),
+            b'\t' => result.push_str("\\t"),
+            b'\n' => result.push_str("\\n"),
+            0x0B => result.push_str("\\v"),
+            0x0C => result.push_str("\\f"),
+            b'\r' => result.push_str("\\r"),
+            c if needs_quote(c) => result.push_str(&format!("\\{:03o}", c)),
+            c => result.push(c as char),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+fn needs_quote(c: u8) -> bool {
+    !(0x20..0x7F).contains(&c) || c == b'"' || c == b'\\'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        assert_eq!(quote_c_style(b"foo/bar.txt"), "foo/bar.txt");
+        assert_eq!(quote_c_style(b"with space"), "with space");
+        assert_eq!(quote_c_style(b""), "");
+    }
+
+    #[test]
+    fn control_characters() {
+        assert_eq!(quote_c_style(b"a\nb"), "\"a\\nb\"");
+        assert_eq!(quote_c_style(b"a\tb\r"), "\"a\\tb\\r\"");
+        assert_eq!(quote_c_style(b"\x07\x08\x0B\x0C"), "\"\\a\\b\\v\\f\"");
+        assert_eq!(quote_c_style(b"\x01\x1F\x7F"), "\"\\001\\037\\177\"");
+    }
+
+    #[test]
+    fn quote_and_backslash() {
+        assert_eq!(quote_c_style(b"say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_c_style(b"a\\b"), "\"a\\\\b\"");
+    }
+
+    #[test]
+    fn high_bytes() {
+        assert_eq!(quote_c_style("caf\u{e9}".as_bytes()), "\"caf\\303\\251\"");
+        assert_eq!(quote_c_style(b"\xFF"), "\"\\377\"");
+    }
+}
@@ -0,0 +1,321 @@
+//! Represents the git concept of a "reference" (ref), which is a
+//! human-readable name that points to an object in the repository.
+
+use std::result::Result;
+
+use thiserror::Error;
+
+use crate::object::Id;
+
+/// Describes what a repository's `HEAD` currently refers to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HeadState {
+    /// `HEAD` refers to a branch which doesn't exist yet, as is the case in
+    /// a freshly-initialized repository. `name` is the full ref name of the
+    /// branch (e.g. `refs/heads/master`).
+    Unborn { name: String },
+
+    /// `HEAD` contains an object ID directly rather than naming a branch.
+    Detached(Id),
+
+    /// `HEAD` refers to an existing branch. `name` is the full ref name of
+    /// the branch (e.g. `refs/heads/master`) and `id` is the object it
+    /// points to.
+    Branch { name: String, id: Id },
+}
+
+/// Reasons why a given byte sequence can not be accepted as a git ref name.
+#[derive(Debug, Eq, Error, PartialEq)]
+pub enum RefNameError {
+    #[error("the ref name is empty")]
+    EmptyName,
+
+    #[error("the ref name contains only one level")]
+    OneLevel,
+
+    #[error("the ref name begins with '/'")]
+    LeadingSlash,
+
+    #[error("the ref name ends with '/'")]
+    TrailingSlash,
+
+    #[error("the ref name contains adjacent '/' separators")]
+    DuplicateSlash,
+
+    #[error("a ref name component begins with '.'")]
+    ComponentBeginsWithDot,
+
+    #[error("a ref name component ends with '.lock'")]
+    ComponentEndsWithLock,
+
+    #[error("the ref name contains '..'")]
+    DoubleDot,
+
+    #[error("the ref name ends with '.'")]
+    EndsWithDot,
+
+    #[error("the ref name contains '@{{'")]
+    ContainsAtBrace,
+
+    #[error("the ref name is '@'")]
+    AtSign,
+
+    #[error("the ref name contains the character `{0}`, which is not allowed")]
+    ContainsInvalidCharacter(char),
+}
+
+/// Describes what a repository's `HEAD` should be changed to refer to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HeadTarget {
+    /// Make `HEAD` a symbolic ref to the named branch. `name` is the full
+    /// ref name (e.g. `refs/heads/master`), not a short branch name such as
+    /// `master`, which is rejected. The branch needn't exist yet.
+    Ref(String),
+
+    /// Detach `HEAD` at the given object ID.
+    Detached(Id),
+}
+
+/// Check that the provided byte sequence is acceptable as a git ref name.
+///
+/// This enforces the same rules as [`git check-ref-format`]. Unless
+/// `allow_onelevel` is `true`, the name must contain at least one `/`
+/// (i.e. `refs/heads/master` is allowed, but `master` is not).
+///
+/// [`git check-ref-format`]: https://git-scm.com/docs/git-check-ref-format
+pub fn validate_ref_name(name: &[u8], allow_onelevel: bool) -> Result<(), RefNameError> {
+    if name.is_empty() {
+        return Err(RefNameError::EmptyName);
+    }
+
+    if name == b"@" {
+        return Err(RefNameError::AtSign);
+    }
+
+    if name.starts_with(b"/") {
+        return Err(RefNameError::LeadingSlash);
+    }
+
+    if name.ends_with(b"/") {
+        return Err(RefNameError::TrailingSlash);
+    }
+
+    let mut component_count = 0;
+    for component in name.split(|c| *c == b'/') {
+        check_component(component)?;
+        component_count += 1;
+    }
+
+    if name.ends_with(b".") {
+        return Err(RefNameError::EndsWithDot);
+    }
+
+    if component_count < 2 && !allow_onelevel {
+        return Err(RefNameError::OneLevel);
+    }
+
+    Ok(())
+}
+
+/// Normalize a ref name in the same way as `git check-ref-format --normalize`.
+///
+/// Removes any leading `/` characters and collapses runs of adjacent `/`
+/// characters into one. The result should still be checked with
+/// [`validate_ref_name`].
+///
+/// [`validate_ref_name`]: fn.validate_ref_name.html
+pub fn normalize_ref_name(name: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(name.len());
+
+    for &c in name {
+        if c == b'/' && (result.is_empty() || result.last() == Some(&b'/')) {
+            continue;
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+fn check_component(component: &[u8]) -> Result<(), RefNameError> {
+    if component.is_empty() {
+        return Err(RefNameError::DuplicateSlash);
+    }
+
+    if component.starts_with(b".") {
+        return Err(RefNameError::ComponentBeginsWithDot);
+    }
+
+    if component.ends_with(b".lock") {
+        return Err(RefNameError::ComponentEndsWithLock);
+    }
+
+    let mut previous: u8 = 0;
+    for &c in component {
+        match c {
+            0..=0x1F | 0x7F | b' ' | b'~' | b'^' | b':' | b'?' | b'*' | b'[' | b'\\' => {
+                return Err(RefNameError::ContainsInvalidCharacter(c as char));
+            }
+            b'.' if previous == b'.' => {
+                return Err(RefNameError::DoubleDot);
+            }
+            b'{' if previous == b'@' => {
+                return Err(RefNameError::ContainsAtBrace);
+            }
+            _ => (),
+        }
+        previous = c;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid(name: &[u8]) -> bool {
+        validate_ref_name(name, false).is_ok()
+    }
+
+    fn valid_onelevel(name: &[u8]) -> bool {
+        validate_ref_name(name, true).is_ok()
+    }
+
+    #[test]
+    fn basic_case() {
+        assert!(valid(b"refs/heads/master"));
+        assert!(valid(b"heads/foo"));
+        assert!(valid(b"foo/bar/baz"));
+        assert!(valid(b"refs/heads/x.y"));
+        assert!(valid(b"refs/heads/@"));
+        assert!(valid(b"refs/heads/a@b"));
+        assert!(valid(b"refs/heads/\xE2\x98\x83"));
+
+        assert_eq!(
+            validate_ref_name(b"", false).unwrap_err(),
+            RefNameError::EmptyName
+        );
+        assert_eq!(
+            validate_ref_name(b"", true).unwrap_err(),
+            RefNameError::EmptyName
+        );
+    }
+
+    #[test]
+    fn onelevel() {
+        assert_eq!(
+            validate_ref_name(b"foo", false).unwrap_err(),
+            RefNameError::OneLevel
+        );
+        assert!(valid_onelevel(b"foo"));
+        assert!(valid_onelevel(b"HEAD"));
+        assert!(valid_onelevel(b"foo/bar"));
+    }
+
+    #[test]
+    fn slashes() {
+        assert_eq!(
+            validate_ref_name(b"/heads/foo", false).unwrap_err(),
+            RefNameError::LeadingSlash
+        );
+        assert_eq!(
+            validate_ref_name(b"heads/foo/", false).unwrap_err(),
+            RefNameError::TrailingSlash
+        );
+        assert_eq!(
+            validate_ref_name(b"foo/", true).unwrap_err(),
+            RefNameError::TrailingSlash
+        );
+        assert_eq!(
+            validate_ref_name(b"heads//foo", false).unwrap_err(),
+            RefNameError::DuplicateSlash
+        );
+        assert_eq!(
+            validate_ref_name(b"/", true).unwrap_err(),
+            RefNameError::LeadingSlash
+        );
+    }
+
+    #[test]
+    fn dots() {
+        assert_eq!(
+            validate_ref_name(b"refs/heads/x..y", false).unwrap_err(),
+            RefNameError::DoubleDot
+        );
+        assert_eq!(
+            validate_ref_name(b"heads/foo.", false).unwrap_err(),
+            RefNameError::EndsWithDot
+        );
+        assert_eq!(
+            validate_ref_name(b"./foo", false).unwrap_err(),
+            RefNameError::ComponentBeginsWithDot
+        );
+        assert_eq!(
+            validate_ref_name(b"heads/.foo", false).unwrap_err(),
+            RefNameError::ComponentBeginsWithDot
+        );
+        assert_eq!(
+            validate_ref_name(b"foo/./bar", false).unwrap_err(),
+            RefNameError::ComponentBeginsWithDot
+        );
+        assert_eq!(
+            validate_ref_name(b"foo/../bar", false).unwrap_err(),
+            RefNameError::ComponentBeginsWithDot
+        );
+        assert!(valid(b"foo./bar"));
+    }
+
+    #[test]
+    fn lock_suffix() {
+        assert_eq!(
+            validate_ref_name(b"heads/foo.lock", false).unwrap_err(),
+            RefNameError::ComponentEndsWithLock
+        );
+        assert_eq!(
+            validate_ref_name(b"foo.lock/bar", false).unwrap_err(),
+            RefNameError::ComponentEndsWithLock
+        );
+        assert!(valid(b"heads/foo.locked"));
+        assert!(valid(b"heads/foo.loc"));
+    }
+
+    #[test]
+    fn at_sign() {
+        assert_eq!(
+            validate_ref_name(b"@", true).unwrap_err(),
+            RefNameError::AtSign
+        );
+        assert_eq!(
+            validate_ref_name(b"heads/foo@{bar", false).unwrap_err(),
+            RefNameError::ContainsAtBrace
+        );
+        assert!(valid(b"heads/foo@bar"));
+        assert!(valid(b"heads/foo{bar"));
+    }
+
+    #[test]
+    fn invalid_characters() {
+        for c in b" ~^:?*[\\\x01\x1F\x7F\0".iter() {
+            let mut name = b"heads/a".to_vec();
+            name.push(*c);
+            name.push(b'b');
+
+            assert_eq!(
+                validate_ref_name(&name, false).unwrap_err(),
+                RefNameError::ContainsInvalidCharacter(*c as char)
+            );
+        }
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_ref_name(b"refs/heads/foo"), b"refs/heads/foo");
+        assert_eq!(normalize_ref_name(b"/heads/foo"), b"heads/foo");
+        assert_eq!(normalize_ref_name(b"///heads//foo"), b"heads/foo");
+        assert_eq!(normalize_ref_name(b"heads/foo/"), b"heads/foo/");
+        assert_eq!(normalize_ref_name(b"heads/foo//"), b"heads/foo/");
+        assert_eq!(normalize_ref_name(b"/"), b"");
+        assert_eq!(normalize_ref_name(b""), b"");
+    }
+}
@@ -2,6 +2,8 @@ use std::path::PathBuf;
 
 use thiserror::Error;
 
+use crate::object::{ContentLengthMismatch, Id};
+
 /// Describes the potential error conditions that might arise from rsgit [`Repo`] operations.
 ///
 /// [`Repo`]: trait.Repo.html
@@ -16,10 +18,31 @@ pub enum Error {
     #[error("git_dir shouldn't exist `{0}`")]
     GitDirShouldntExist(PathBuf),
 
-    #[error(transparent)]
+    #[error("template_dir doesn't exist `{0}`")]
+    TemplateDirDoesntExist(PathBuf),
+
+    #[error("object store is corrupt: `{0}` should be a directory")]
+    ObjectStoreCorrupt(PathBuf),
+
+    #[error("object `{0}` not found")]
+    ObjectNotFound(Id),
+
+    #[error("object file for `{0}` contains object `{1}`")]
+    ObjectIdMismatch(Id, Id),
+
+    #[error("object `{0}` is corrupt")]
+    ObjectLengthMismatch(Id, #[source] ContentLengthMismatch),
+
+    #[error("invalid value `{1}` for config option `{0}`")]
+    InvalidConfigValue(String, String),
+
+    #[error("ref `{0}` is malformed")]
+    InvalidRef(String),
+
+    #[error("I/O error")]
     IoError(#[from] std::io::Error),
 
-    #[error(transparent)]
+    #[error("other error")]
     OtherError(#[from] Box<dyn std::error::Error>),
 }
 
@@ -28,3 +51,156 @@ pub enum Error {
 /// [`Repo`]: trait.Repo.html
 /// [`Result`]: https://doc.rust-lang.org/std/result/enum.Result.html
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error as _, io, path::PathBuf};
+
+    use super::*;
+
+    #[test]
+    fn path_variants_display_path() {
+        let path = PathBuf::from("/some/where");
+
+        let err = Error::WorkDirDoesntExist(path.clone());
+        assert_eq!(err.to_string(), "work_dir doesn't exist `/some/where`");
+        assert!(err.source().is_none());
+
+        let err = Error::GitDirDoesntExist(path.clone());
+        assert_eq!(err.to_string(), "git_dir doesn't exist `/some/where`");
+        assert!(err.source().is_none());
+
+        let err = Error::GitDirShouldntExist(path.clone());
+        assert_eq!(err.to_string(), "git_dir shouldn't exist `/some/where`");
+        assert!(err.source().is_none());
+
+        let err = Error::TemplateDirDoesntExist(path.clone());
+        assert_eq!(err.to_string(), "template_dir doesn't exist `/some/where`");
+        assert!(err.source().is_none());
+
+        let err = Error::ObjectStoreCorrupt(path);
+        assert_eq!(
+            err.to_string(),
+            "object store is corrupt: `/some/where` should be a directory"
+        );
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn invalid_config_value() {
+        let err = Error::InvalidConfigValue("core.bare".to_string(), "maybe".to_string());
+        assert_eq!(
+            err.to_string(),
+            "invalid value `maybe` for config option `core.bare`"
+        );
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn object_not_found() {
+        let id = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+        let err = Error::ObjectNotFound(id);
+        assert_eq!(
+            err.to_string(),
+            "object `d670460b4b4aece5915caf5c68d12f560a9fe3e4` not found"
+        );
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn object_id_mismatch() {
+        let expected = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+        let actual = Id::from_hex("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").unwrap();
+        let err = Error::ObjectIdMismatch(expected, actual);
+        assert_eq!(
+            err.to_string(),
+            "object file for `d670460b4b4aece5915caf5c68d12f560a9fe3e4` contains object `e69de29bb2d1d6434b8b29ae775ad8c2e48c5391`"
+        );
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn object_length_mismatch_is_source() {
+        let id = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+        let mismatch = ContentLengthMismatch {
+            expected: 20,
+            actual: 13,
+        };
+        let err = Error::ObjectLengthMismatch(id, mismatch);
+        assert_eq!(
+            err.to_string(),
+            "object `d670460b4b4aece5915caf5c68d12f560a9fe3e4` is corrupt"
+        );
+
+        let source = err.source().unwrap();
+        assert_eq!(
+            source.downcast_ref::<ContentLengthMismatch>().unwrap(),
+            &ContentLengthMismatch {
+                expected: 20,
+                actual: 13
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_ref() {
+        let err = Error::InvalidRef("HEAD".to_string());
+        assert_eq!(err.to_string(), "ref `HEAD` is malformed");
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn io_error_is_source() {
+        let err: Error = io::Error::new(io::ErrorKind::NotFound, "no such thing").into();
+        assert_eq!(err.to_string(), "I/O error");
+
+        let source = err.source().unwrap();
+        let io_err = source.downcast_ref::<io::Error>().unwrap();
+        assert_eq!(io_err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn other_error_is_source() {
+        let inner: Box<dyn std::error::Error> = "something else".into();
+        let err: Error = inner.into();
+        assert_eq!(err.to_string(), "other error");
+
+        let source = err.source().unwrap();
+        assert_eq!(source.to_string(), "something else");
+    }
+
+    #[test]
+    fn source_chain_doesnt_repeat_messages() {
+        let id = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+        let inner: Box<dyn std::error::Error> = "something else".into();
+
+        let errs = vec![
+            Error::from(io::Error::new(io::ErrorKind::NotFound, "no such thing")),
+            Error::from(inner),
+            Error::ObjectLengthMismatch(
+                id,
+                ContentLengthMismatch {
+                    expected: 20,
+                    actual: 13,
+                },
+            ),
+        ];
+
+        for err in &errs {
+            let mut messages = vec![err.to_string()];
+            let mut source = err.source();
+            while let Some(s) = source {
+                let message = s.to_string();
+                assert!(
+                    !messages.iter().any(|m| m.contains(&message)),
+                    "message {:?} repeated in chain {:?}",
+                    message,
+                    messages
+                );
+                messages.push(message);
+                source = s.source();
+            }
+            assert_eq!(messages.len(), 2);
+        }
+    }
+}
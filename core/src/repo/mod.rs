@@ -9,7 +9,10 @@
 //!
 //! [`OnDiskRepo`]: struct.OnDiskRepo.html
 
-use crate::object::Object;
+use crate::{
+    object::{Id, Object},
+    refs::{HeadState, HeadTarget},
+};
 
 mod error;
 pub use error::{Error, Result};
@@ -25,10 +28,80 @@ pub use error::{Error, Result};
 /// The provided methods on this trait represent the common "porcelain" and "plumbing"
 /// operations for a git repo, regardless of its storage mechanism.
 pub trait Repo {
+    /// Returns the human-readable description of the repository, as shown
+    /// by tools such as gitweb. A trailing newline is removed.
+    fn description(&self) -> Result<String>;
+
+    /// Replaces the description of the repository.
+    fn set_description(&mut self, text: &str) -> Result<()>;
+
+    /// Returns `true` if the repository contains an object with the given ID.
+    fn has_object(&self, id: &Id) -> Result<bool>;
+
+    /// Describes what `HEAD` currently refers to: a branch (which may not
+    /// exist yet) or a specific object (a "detached" `HEAD`).
+    fn head_state(&self) -> Result<HeadState>;
+
+    /// Returns `true` if the repository has no commits: `HEAD` refers to a
+    /// branch that doesn't exist yet, and there are no refs at all.
+    fn is_empty(&self) -> Result<bool>;
+
+    /// Reads a loose object from the repository.
+    ///
+    /// Returns [`Error::ObjectNotFound`] if there is no loose object with
+    /// the given ID. Returns [`Error::ObjectLengthMismatch`] if the object's
+    /// content doesn't match the length given in its header, and
+    /// [`Error::ObjectIdMismatch`] if the content doesn't hash to the given ID.
+    /// This is analogous to [`git cat-file`].
+    ///
+    /// [`Error::ObjectNotFound`]: enum.Error.html#variant.ObjectNotFound
+    /// [`Error::ObjectLengthMismatch`]: enum.Error.html#variant.ObjectLengthMismatch
+    /// [`Error::ObjectIdMismatch`]: enum.Error.html#variant.ObjectIdMismatch
+    /// [`git cat-file`]: https://git-scm.com/docs/git-cat-file
+    fn get_loose_object(&self, id: &Id) -> Result<Object<'_>>;
+
+    /// Changes what `HEAD` refers to.
+    ///
+    /// This is analogous to [`git symbolic-ref HEAD`] (for a branch) or
+    /// [`git update-ref --no-deref HEAD`] (to detach `HEAD`).
+    ///
+    /// [`git symbolic-ref HEAD`]: https://git-scm.com/docs/git-symbolic-ref
+    /// [`git update-ref --no-deref HEAD`]: https://git-scm.com/docs/git-update-ref
+    fn set_head(&mut self, target: HeadTarget) -> Result<()>;
+
     /// Writes a loose object to the repository.
     ///
     /// This is analogous to [`git hash-object -w`].
     ///
     /// [`git hash-object -w`]: https://git-scm.com/docs/git-hash-object#Documentation/git-hash-object.txt--w
     fn put_loose_object(&mut self, object: &Object) -> Result<()>;
+
+    /// Writes an object to the repository and returns its ID, along with
+    /// whether the object was actually written.
+    ///
+    /// Unlike [`put_loose_object`], this does nothing (and is not an error)
+    /// if the repository already contains the object.
+    ///
+    /// [`put_loose_object`]: #tymethod.put_loose_object
+    fn write_object(&mut self, object: &Object) -> Result<(Id, WriteOutcome)> {
+        let id = object.id().clone();
+        if self.has_object(&id)? {
+            return Ok((id, WriteOutcome::AlreadyExisted));
+        }
+
+        self.put_loose_object(object)?;
+        Ok((id, WriteOutcome::Created))
+    }
+}
+
+/// Describes whether [`Repo::write_object`] wrote a new object.
+///
+/// [`Repo::write_object`]: trait.Repo.html#method.write_object
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WriteOutcome {
+    /// The object was written to the repository.
+    Created,
+
+    /// The repository already contained the object, so nothing was written.
+    AlreadyExisted,
 }
@@ -13,4 +13,6 @@ mod on_disk_repo;
 pub use on_disk_repo::OnDiskRepo;
 
 mod temp_git_repo;
-pub use temp_git_repo::TempGitRepo;
+pub use temp_git_repo::{TempGitRepo, TempGitRepoBuilder};
+
+pub mod test_support;
@@ -0,0 +1,222 @@
+use std::{fs, io, path::Path};
+
+// The parsed content of a git config file.
+//
+// Section and key names are matched case-insensitively, as git does.
+// Subsections (`[remote "origin"]`) are not yet supported and are skipped.
+// If a value appears more than once, the last occurrence wins.
+//
+// This is intentionally minimal: it doesn't handle quoting, escapes,
+// line continuations, or `include` directives.
+#[derive(Debug, Default)]
+pub(crate) struct Config {
+    // (`section.key` in lower case, value) in file order.
+    entries: Vec<(String, String)>,
+}
+
+impl Config {
+    // Read and parse a git config file. A missing file is treated as empty.
+    pub(crate) fn read(config_path: &Path) -> io::Result<Config> {
+        match fs::read_to_string(config_path) {
+            Ok(txt) => Ok(Config::parse(&txt)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn parse(config_txt: &str) -> Config {
+        let mut section: Option<String> = None;
+        let mut entries = Vec::new();
+
+        for line in config_txt.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                section = match line.find(']') {
+                    Some(n) if !line[1..n].contains('"') => {
+                        Some(line[1..n].trim().to_ascii_lowercase())
+                    }
+                    _ => None,
+                };
+                continue;
+            }
+
+            let section = match &section {
+                Some(section) => section,
+                None => continue,
+            };
+
+            let (key, value) = match line.find('=') {
+                Some(n) => (line[..n].trim(), line[n + 1..].trim()),
+                None => (line, "true"),
+                // A key with no `=` is git's shorthand for a true boolean.
+            };
+
+            entries.push((
+                format!("{}.{}", section, key.to_ascii_lowercase()),
+                value.to_string(),
+            ));
+        }
+
+        Config { entries }
+    }
+
+    // Look up a single value. `name` is given in git's `section.key` form
+    // (e.g. `core.bare`). Returns `None` if the value doesn't exist.
+    pub(crate) fn value(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// Set each `key = value` pair in `section` of the config text, returning
+// the updated text. As `git config` does, an existing key is updated in
+// place, a new key is added at the end of the section, and a missing
+// section is added at the end of the file. Everything else is preserved.
+pub(crate) fn set_values(config_txt: &str, section: &str, values: &[(&str, &str)]) -> String {
+    let mut lines: Vec<String> = config_txt.lines().map(|l| l.to_string()).collect();
+
+    for (key, value) in values {
+        let new_line = format!("\t{} = {}", key, value);
+
+        let mut existing = None;
+        let mut section_end = None;
+        let mut in_section = false;
+
+        for (n, line) in lines.iter().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.starts_with('[') {
+                in_section = match line.find(']') {
+                    Some(end) => line[1..end].trim().eq_ignore_ascii_case(section),
+                    None => false,
+                };
+                if in_section {
+                    section_end = Some(n);
+                }
+            } else if in_section && !line.is_empty() {
+                section_end = Some(n);
+                let name = line.split('=').next().unwrap_or("").trim();
+                if name.eq_ignore_ascii_case(key) {
+                    existing = Some(n);
+                }
+            }
+        }
+
+        match (existing, section_end) {
+            (Some(n), _) => lines[n] = new_line,
+            (None, Some(n)) => lines.insert(n + 1, new_line),
+            (None, None) => {
+                lines.push(format!("[{}]", section));
+                lines.push(new_line);
+            }
+        }
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find(&['#', ';'][..]) {
+        Some(n) => &line[..n],
+        None => line,
+    }
+}
+
+// Interpret a config value as a boolean using git's rules.
+//
+// Returns `None` if the value isn't a recognized boolean.
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" | "" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG_TXT: &str = "[core]\n\trepositoryformatversion = 0\n\tfilemode = true\n\tbare = false\n\tlogallrefupdates = true\n";
+
+    #[test]
+    fn value_fn() {
+        let config = Config::parse(CONFIG_TXT);
+        assert_eq!(config.value("core.bare").unwrap(), "false");
+        assert_eq!(config.value("core.Bare").unwrap(), "false");
+        assert_eq!(config.value("CORE.filemode").unwrap(), "true");
+        assert_eq!(config.value("core.repositoryformatversion").unwrap(), "0");
+
+        assert!(config.value("core.bogus").is_none());
+        assert!(config.value("user.bare").is_none());
+        assert!(config.value("bare").is_none());
+    }
+
+    #[test]
+    fn value_edge_cases() {
+        let txt = "# comment\n[Core] ; comment\n\tbare\n[user]\n\tbare = false\n";
+        assert_eq!(Config::parse(txt).value("core.bare").unwrap(), "true");
+
+        let txt = "[core]\n\tbare = false\n[core]\n\tbare = true # comment\n";
+        assert_eq!(Config::parse(txt).value("core.bare").unwrap(), "true");
+
+        let txt = "[remote \"origin\"]\n\tbare = true\n";
+        let config = Config::parse(txt);
+        assert!(config.value("core.bare").is_none());
+        assert!(config.value("remote \"origin\".bare").is_none());
+
+        let txt = "\tbare = true\n[core]\n";
+        assert!(Config::parse(txt).value("core.bare").is_none());
+    }
+
+    #[test]
+    fn set_values_fn() {
+        assert_eq!(
+            set_values("", "core", &[("bare", "false"), ("filemode", "true")]),
+            "[core]\n\tbare = false\n\tfilemode = true\n"
+        );
+
+        let txt = "[user]\n\tname = x\n";
+        assert_eq!(
+            set_values(txt, "core", &[("bare", "false")]),
+            "[user]\n\tname = x\n[core]\n\tbare = false\n"
+        );
+
+        let txt = "[Core]\n\tBare = true\n\tfoo = y\n\n[user]\n\tname = x\n";
+        assert_eq!(
+            set_values(txt, "core", &[("bare", "false"), ("filemode", "true")]),
+            "[Core]\n\tbare = false\n\tfoo = y\n\tfilemode = true\n\n[user]\n\tname = x\n"
+        );
+    }
+
+    #[test]
+    fn read_missing_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = Config::read(&temp_dir.path().join("config")).unwrap();
+        assert!(config.value("core.bare").is_none());
+    }
+
+    #[test]
+    fn parse_bool_fn() {
+        assert_eq!(parse_bool("true"), Some(true));
+        assert_eq!(parse_bool("Yes"), Some(true));
+        assert_eq!(parse_bool("on"), Some(true));
+        assert_eq!(parse_bool("1"), Some(true));
+
+        assert_eq!(parse_bool("false"), Some(false));
+        assert_eq!(parse_bool("NO"), Some(false));
+        assert_eq!(parse_bool("off"), Some(false));
+        assert_eq!(parse_bool("0"), Some(false));
+        assert_eq!(parse_bool(""), Some(false));
+
+        assert_eq!(parse_bool("maybe"), None);
+    }
+}
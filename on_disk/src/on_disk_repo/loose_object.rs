@@ -0,0 +1,249 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Error, ErrorKind, Read},
+    path::{Path, PathBuf},
+    str,
+};
+
+use flate2::read::ZlibDecoder;
+
+use rsgit_core::object::{ContentSource, ContentSourceOpenResult, Id, Kind, DEFAULT_BUFFER_SIZE};
+
+use sha1::{Digest, Sha1};
+
+// Longest loose object header (`<kind> <len>\0`) we'll accept. Command-line
+// git allows arbitrary kind names only with `--literally`, so this is
+// generous for the built-in kinds.
+const MAX_HEADER_LEN: u64 = 1024;
+
+// Implements `ContentSource` for a loose object file. Each call to `open`
+// inflates the file again and skips past the header.
+pub(crate) struct LooseObjectContentSource {
+    path: PathBuf,
+    kind: Kind,
+    len: u64,
+}
+
+impl LooseObjectContentSource {
+    // Read the header of the loose object at `path`, returning its kind
+    // and a content source for its content.
+    pub(crate) fn new(path: &Path) -> io::Result<(Kind, LooseObjectContentSource)> {
+        let mut r = open_inflated(path)?;
+        let (kind, len) = read_header(&mut r)?;
+
+        Ok((
+            kind.clone(),
+            LooseObjectContentSource {
+                path: path.to_path_buf(),
+                kind,
+                len,
+            },
+        ))
+    }
+
+    // Open the object's content as `open` does, but also hash it while
+    // it's read. Once the content has been read to the end, the reader
+    // fails with `InvalidData` if the content doesn't hash to `id` or
+    // doesn't match the length given in the header.
+    pub(crate) fn verified_open(&self, id: &Id) -> io::Result<Box<dyn BufRead>> {
+        let mut r = open_inflated(&self.path)?;
+        read_header(&mut r)?;
+
+        let mut hasher = Sha1::new();
+        hasher.update(header_bytes(&self.kind, self.len));
+
+        Ok(Box::new(BufReader::with_capacity(
+            DEFAULT_BUFFER_SIZE,
+            VerifyingReader {
+                inner: r,
+                hasher,
+                id: id.clone(),
+                expected_len: self.len,
+                actual_len: 0,
+                verified: false,
+            },
+        )))
+    }
+}
+
+impl ContentSource for LooseObjectContentSource {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn open(&self) -> ContentSourceOpenResult {
+        let mut r = open_inflated(&self.path)?;
+        read_header(&mut r)?;
+        Ok(Box::new(r))
+    }
+}
+
+// Feeds everything read through SHA-1 and checks the digest (and length)
+// at EOF, so the content is verified without a second pass.
+struct VerifyingReader<R> {
+    inner: R,
+    hasher: Sha1,
+    id: Id,
+    expected_len: u64,
+    actual_len: u64,
+    verified: bool,
+}
+
+impl<R: Read> Read for VerifyingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+
+        if n > 0 {
+            self.hasher.update(&buf[..n]);
+            self.actual_len += n as u64;
+        } else if !buf.is_empty() && !self.verified {
+            self.verify()?;
+        }
+
+        Ok(n)
+    }
+}
+
+impl<R> VerifyingReader<R> {
+    fn verify(&mut self) -> io::Result<()> {
+        if self.actual_len != self.expected_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "loose object `{}` is corrupt: header says {} bytes, but content is {} bytes",
+                    self.id, self.expected_len, self.actual_len
+                ),
+            ));
+        }
+
+        let hash: [u8; 20] = self.hasher.finalize_reset().into();
+        let actual_id = Id::from(hash);
+        if actual_id != self.id {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "loose object `{}` is corrupt: content hashes to `{}`",
+                    self.id, actual_id
+                ),
+            ));
+        }
+
+        self.verified = true;
+        Ok(())
+    }
+}
+
+// The header as it appears at the start of the inflated loose object.
+fn header_bytes(kind: &Kind, len: u64) -> Vec<u8> {
+    let mut header = match kind {
+        Kind::Other(name) => name.clone(),
+        kind => kind.to_string().into_bytes(),
+    };
+    header.extend_from_slice(format!(" {}\0", len).as_bytes());
+    header
+}
+
+fn open_inflated(path: &Path) -> io::Result<BufReader<ZlibDecoder<File>>> {
+    let file = File::open(path)?;
+    Ok(BufReader::with_capacity(
+        DEFAULT_BUFFER_SIZE,
+        ZlibDecoder::new(file),
+    ))
+}
+
+// Read and parse the `<kind> <len>\0` header, leaving `r` positioned
+// at the start of the object's content.
+fn read_header<R: BufRead>(r: &mut R) -> io::Result<(Kind, u64)> {
+    let mut header = Vec::new();
+    r.by_ref().take(MAX_HEADER_LEN).read_until(0, &mut header)?;
+
+    if header.pop() != Some(0) {
+        return Err(invalid_header());
+    }
+
+    parse_header(&header).ok_or_else(invalid_header)
+}
+
+fn parse_header(header: &[u8]) -> Option<(Kind, u64)> {
+    let space = header.iter().position(|c| *c == b' ')?;
+    let (kind, len) = (&header[..space], &header[space + 1..]);
+
+    let kind = match kind {
+        b"blob" => Kind::Blob,
+        b"commit" => Kind::Commit,
+        b"tag" => Kind::Tag,
+        b"tree" => Kind::Tree,
+        b"" => return None,
+        other => Kind::Other(other.to_vec()),
+    };
+
+    // Like git, reject signs, leading zeros, and anything but digits.
+    if len.is_empty() || (len.len() > 1 && len[0] == b'0') {
+        return None;
+    }
+    if !len.iter().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let len = str::from_utf8(len).ok()?.parse().ok()?;
+
+    Some((kind, len))
+}
+
+fn invalid_header() -> Error {
+    Error::new(ErrorKind::InvalidData, "malformed loose object header")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_header_fn() {
+        assert_eq!(parse_header(b"blob 13"), Some((Kind::Blob, 13)));
+        assert_eq!(parse_header(b"commit 0"), Some((Kind::Commit, 0)));
+        assert_eq!(parse_header(b"tag 1"), Some((Kind::Tag, 1)));
+        assert_eq!(parse_header(b"tree 123456"), Some((Kind::Tree, 123_456)));
+        assert_eq!(
+            parse_header(b"bogus 5"),
+            Some((Kind::Other(b"bogus".to_vec()), 5))
+        );
+
+        assert_eq!(parse_header(b"blob"), None);
+        assert_eq!(parse_header(b"blob "), None);
+        assert_eq!(parse_header(b" 13"), None);
+        assert_eq!(parse_header(b"blob 013"), None);
+        assert_eq!(parse_header(b"blob +13"), None);
+        assert_eq!(parse_header(b"blob 1 3"), None);
+        assert_eq!(parse_header(b"blob 99999999999999999999999"), None);
+    }
+
+    #[test]
+    fn header_bytes_fn() {
+        assert_eq!(header_bytes(&Kind::Blob, 13), b"blob 13\0");
+        assert_eq!(header_bytes(&Kind::Tree, 0), b"tree 0\0");
+        assert_eq!(
+            header_bytes(&Kind::Other(b"b\xffgus".to_vec()), 5),
+            b"b\xffgus 5\0"
+        );
+    }
+
+    #[test]
+    fn read_header_fn() {
+        let mut r: &[u8] = b"blob 13\0test content\n";
+        assert_eq!(read_header(&mut r).unwrap(), (Kind::Blob, 13));
+        assert_eq!(r, b"test content\n");
+
+        let mut r: &[u8] = b"blob 13";
+        assert_eq!(
+            read_header(&mut r).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        let long_header = vec![b'x'; 2 * MAX_HEADER_LEN as usize];
+        let mut r: &[u8] = &long_header;
+        assert_eq!(
+            read_header(&mut r).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+}
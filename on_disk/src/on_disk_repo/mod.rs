@@ -1,16 +1,26 @@
 use std::{
+    ffi::OsStr,
     fs::{self, OpenOptions},
-    io::{self, Write},
+    io::{self, BufRead, Write},
     path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
 };
 
 use flate2::{write::ZlibEncoder, Compression};
 
 use rsgit_core::{
-    object::Object,
+    object::{ContentLengthMismatch, Id, Object},
+    refs::{HeadState, HeadTarget},
     repo::{Error, Repo, Result},
 };
 
+mod config;
+use config::Config;
+
+mod loose_object;
+use loose_object::LooseObjectContentSource;
+mod refs;
+
 /// Implementation of [`Repo`] that stores content on the local file system.
 ///
 /// _IMPORTANT NOTE:_ This is intended as a reference implementation largely
@@ -23,8 +33,10 @@ use rsgit_core::{
 /// [`Repo`]: ../rsgit_core/repo/trait.Repo.html
 #[derive(Debug)]
 pub struct OnDiskRepo {
-    work_dir: PathBuf,
+    work_dir: Option<PathBuf>,
     git_dir: PathBuf,
+    objects_dir: PathBuf,
+    config: Config,
 }
 
 impl OnDiskRepo {
@@ -34,7 +46,12 @@ impl OnDiskRepo {
     /// exist at this path. Use [`init`] function to create an empty on-disk repository if
     /// necessary.
     ///
+    /// Objects are read from and written to `.git/objects` unless the
+    /// repository's config sets `core.objectDirectory`. (See also
+    /// [`set_objects_dir`].)
+    ///
     /// [`init`]: #method.init
+    /// [`set_objects_dir`]: #method.set_objects_dir
     pub fn new<P: AsRef<Path>>(work_dir: P) -> Result<Self> {
         let work_dir = work_dir.as_ref().to_path_buf();
         if !work_dir.exists() {
@@ -46,7 +63,63 @@ impl OnDiskRepo {
             return Err(Error::GitDirDoesntExist(git_dir));
         }
 
-        Ok(OnDiskRepo { work_dir, git_dir })
+        let config = Config::read(&git_dir.join("config"))?;
+        let objects_dir = configured_objects_dir(&git_dir, &config);
+
+        Ok(OnDiskRepo {
+            work_dir: Some(work_dir),
+            git_dir,
+            objects_dir,
+            config,
+        })
+    }
+
+    /// Open an existing on-disk git repository, which may be bare or non-bare.
+    ///
+    /// `path` may be either a top-level working directory containing a `.git`
+    /// directory or a git directory itself (as for a bare repository). In the
+    /// latter case, the `core.bare` setting from the repository's config
+    /// determines whether the repo is bare (no working directory) or whether
+    /// the parent directory is its working directory.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(Error::WorkDirDoesntExist(path.to_path_buf()));
+        }
+
+        let dot_git = path.join(".git");
+        let (git_dir, work_dir) = if dot_git.is_dir() {
+            (dot_git, Some(path.to_path_buf()))
+        } else if is_git_dir(path) {
+            (path.to_path_buf(), path.parent().map(|p| p.to_path_buf()))
+        } else {
+            return Err(Error::GitDirDoesntExist(dot_git));
+        };
+
+        let config = Config::read(&git_dir.join("config"))?;
+
+        let bare = match config.value("core.bare") {
+            Some(value) => match config::parse_bool(value) {
+                Some(bare) => bare,
+                None => {
+                    return Err(Error::InvalidConfigValue(
+                        "core.bare".to_string(),
+                        value.to_string(),
+                    ))
+                }
+            },
+            None => git_dir.file_name() != Some(OsStr::new(".git")),
+        };
+
+        let work_dir = if bare { None } else { work_dir };
+        let objects_dir = configured_objects_dir(&git_dir, &config);
+
+        Ok(OnDiskRepo {
+            work_dir,
+            git_dir,
+            objects_dir,
+            config,
+        })
     }
 
     /// Creates a new, empty git repository on the local file system.
@@ -55,57 +128,314 @@ impl OnDiskRepo {
     ///
     /// [`git init`]: https://git-scm.com/docs/git-init
     pub fn init<P: AsRef<Path>>(work_dir: P) -> Result<Self> {
-        let git_dir = work_dir.as_ref().join(".git");
+        OnDiskRepo::init_impl(work_dir.as_ref(), None)
+    }
+
+    /// Creates a new git repository as [`init`] does, but first copies the
+    /// contents of `template_dir` into the new `.git` directory.
+    ///
+    /// This is analogous to [`git init --template`]. Files from the template
+    /// (e.g. `hooks/pre-commit` or `info/exclude`) are used in place of the
+    /// default files of the same name. As with command-line git, a `config`
+    /// file from the template is kept, but the `core` settings that `init`
+    /// writes are always set.
+    ///
+    /// [`init`]: #method.init
+    /// [`git init --template`]: https://git-scm.com/docs/git-init#Documentation/git-init.txt---templatelttemplate-directorygt
+    pub fn init_with_template<P: AsRef<Path>, T: AsRef<Path>>(
+        work_dir: P,
+        template_dir: T,
+    ) -> Result<Self> {
+        let template_dir = template_dir.as_ref();
+        if !template_dir.is_dir() {
+            return Err(Error::TemplateDirDoesntExist(template_dir.to_path_buf()));
+        }
+
+        OnDiskRepo::init_impl(work_dir.as_ref(), Some(template_dir))
+    }
+
+    fn init_impl(work_dir: &Path, template_dir: Option<&Path>) -> Result<Self> {
+        let git_dir = work_dir.join(".git");
         if git_dir.exists() {
             return Err(Error::GitDirShouldntExist(git_dir));
         }
 
         fs::create_dir_all(&git_dir)?;
 
+        if let Some(template_dir) = template_dir {
+            copy_template(template_dir, &git_dir)?;
+        }
+
+        let objects_dir = git_dir.join("objects");
+
         create_config(&git_dir)?;
         create_description(&git_dir)?;
         create_head(&git_dir)?;
         create_hooks_dir(&git_dir)?;
         create_info_dir(&git_dir)?;
-        create_objects_dir(&git_dir)?;
+        create_objects_dir(&objects_dir)?;
         create_refs_dir(&git_dir)?;
 
+        let config = Config::read(&git_dir.join("config"))?;
+
         Ok(OnDiskRepo {
-            work_dir: work_dir.as_ref().to_path_buf(),
+            work_dir: Some(work_dir.to_path_buf()),
             git_dir,
+            objects_dir,
+            config,
         })
     }
 
     /// Return the working directory for this repo.
-    pub fn work_dir(&self) -> &Path {
-        self.work_dir.as_path()
+    ///
+    /// Returns `None` if this is a bare repository.
+    pub fn work_dir(&self) -> Option<&Path> {
+        self.work_dir.as_deref()
+    }
+
+    /// Returns `true` if this repository has no working directory.
+    pub fn is_bare(&self) -> bool {
+        self.work_dir.is_none()
     }
 
     /// Return the path to the `.git` directory.
+    ///
+    /// For a bare repository, this is the repository's top-level directory.
     pub fn git_dir(&self) -> &Path {
         self.git_dir.as_path()
     }
+
+    /// Return the path to the object store (typically `.git/objects`).
+    pub fn objects_dir(&self) -> &Path {
+        self.objects_dir.as_path()
+    }
+
+    /// Look up a value from the repository's config file.
+    ///
+    /// `name` is given in git's `section.key` form (e.g. `core.bare`)
+    /// and is matched case-insensitively. Returns `None` if the value
+    /// isn't set. Subsections (e.g. `remote.origin.url`) are not yet
+    /// supported.
+    ///
+    /// The config file is read when the repo is opened; use
+    /// [`reload_config`] to pick up later changes.
+    ///
+    /// [`reload_config`]: #method.reload_config
+    pub fn config_value(&self, name: &str) -> Option<&str> {
+        self.config.value(name)
+    }
+
+    /// Re-read the repository's config file.
+    pub fn reload_config(&mut self) -> Result<()> {
+        self.config = Config::read(&self.git_dir.join("config"))?;
+        Ok(())
+    }
+
+    /// Run the hook named `name` (e.g. `post-commit`) from the
+    /// repository's `hooks` directory, if present.
+    ///
+    /// The hook is run with the given arguments and `stdin` content, with
+    /// the working directory (or, for a bare repo, the git directory) as
+    /// its current directory. Returns the hook's exit status, or `None`
+    /// if there is no such hook or it isn't executable.
+    ///
+    /// See [githooks] for the hooks command-line git supports.
+    ///
+    /// [githooks]: https://git-scm.com/docs/githooks
+    pub fn run_hook(&self, name: &str, args: &[&str], stdin: &[u8]) -> Result<Option<ExitStatus>> {
+        let hook_path = self.git_dir.join("hooks").join(name);
+        if !is_executable(&hook_path) {
+            return Ok(None);
+        }
+
+        let current_dir = self.work_dir.as_ref().unwrap_or(&self.git_dir);
+
+        let mut child = Command::new(&hook_path)
+            .args(args)
+            .current_dir(current_dir)
+            .stdin(Stdio::piped())
+            .spawn()?;
+
+        // Like command-line git, don't fail if the hook exits
+        // without reading all of its input.
+        let mut child_stdin = child.stdin.take().unwrap();
+        match child_stdin.write_all(stdin) {
+            Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(err.into()),
+            _ => (),
+        }
+        drop(child_stdin);
+
+        Ok(Some(child.wait()?))
+    }
+
+    /// Relocate the object store for this repo.
+    ///
+    /// All subsequent object reads and writes will use this directory
+    /// instead of `.git/objects`. This is analogous to setting
+    /// `GIT_OBJECT_DIRECTORY` for command-line git, which callers
+    /// should read and pass in here if they want to honor it.
+    /// The directory is not created or populated by this call.
+    pub fn set_objects_dir<P: AsRef<Path>>(&mut self, objects_dir: P) {
+        self.objects_dir = objects_dir.as_ref().to_path_buf();
+    }
+
+    /// Open the content of a loose object for reading, verifying it
+    /// while it's read.
+    ///
+    /// Unlike [`get_loose_object`], this doesn't read the object before
+    /// returning. Instead, the content is hashed as it's read, and once the
+    /// end is reached, the reader fails with [`io::ErrorKind::InvalidData`]
+    /// if the content doesn't hash to `id`. The whole object is never
+    /// buffered, and it's only read once.
+    ///
+    /// Returns [`Error::ObjectNotFound`] if there is no loose object with
+    /// the given ID.
+    ///
+    /// [`get_loose_object`]: #method.get_loose_object
+    /// [`io::ErrorKind::InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    /// [`Error::ObjectNotFound`]: ../rsgit_core/repo/enum.Error.html#variant.ObjectNotFound
+    pub fn verified_open_loose_object(&self, id: &Id) -> Result<Box<dyn BufRead>> {
+        let object_path = self.objects_dir.join(object_subpath(id));
+        if !object_path.is_file() {
+            return Err(Error::ObjectNotFound(id.clone()));
+        }
+
+        let (_kind, content_source) = LooseObjectContentSource::new(&object_path)?;
+        content_source.verified_open(id).map_err(|e| e.into())
+    }
 }
 
 impl Repo for OnDiskRepo {
-    fn put_loose_object(&mut self, object: &Object) -> Result<()> {
-        let object_id = object.id().to_string();
-        let (dir, path) = object_id.split_at(2);
+    fn description(&self) -> Result<String> {
+        let desc_path = self.git_dir.join("description");
+        match fs::read_to_string(desc_path) {
+            Ok(mut desc_txt) => {
+                if desc_txt.ends_with('\n') {
+                    desc_txt.pop();
+                }
+                Ok(desc_txt)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn set_description(&mut self, text: &str) -> Result<()> {
+        let desc_path = self.git_dir.join("description");
+        let mut desc_txt = text.to_string();
+        if !desc_txt.ends_with('\n') {
+            desc_txt.push('\n');
+        }
+
+        fs::write(desc_path, desc_txt).map_err(|e| e.into())
+    }
+
+    fn head_state(&self) -> Result<HeadState> {
+        refs::head_state(&self.git_dir)
+    }
+
+    fn is_empty(&self) -> Result<bool> {
+        match self.head_state()? {
+            HeadState::Unborn { .. } => Ok(!refs::has_any_refs(&self.git_dir)?),
+            _ => Ok(false),
+        }
+    }
+
+    fn get_loose_object(&self, id: &Id) -> Result<Object<'_>> {
+        let object_path = self.objects_dir.join(object_subpath(id));
+        if !object_path.is_file() {
+            return Err(Error::ObjectNotFound(id.clone()));
+        }
+
+        let (kind, content_source) = LooseObjectContentSource::new(&object_path)?;
+        let object =
+            Object::new(&kind, Box::new(content_source)).map_err(|err| match err
+                .downcast::<ContentLengthMismatch>(
+            ) {
+                Ok(mismatch) => Error::ObjectLengthMismatch(id.clone(), *mismatch),
+                Err(err) => err.into(),
+            })?;
+
+        if object.id() != id {
+            return Err(Error::ObjectIdMismatch(id.clone(), object.id().clone()));
+        }
+
+        Ok(object)
+    }
 
-        let mut object_path = self.git_dir.join("objects");
-        object_path.push(dir);
-        fs::create_dir(&object_path)?;
+    fn set_head(&mut self, target: HeadTarget) -> Result<()> {
+        refs::set_head(&self.git_dir, target)
+    }
+
+    fn has_object(&self, id: &Id) -> Result<bool> {
+        Ok(self.objects_dir.join(object_subpath(id)).is_file())
+    }
+
+    fn put_loose_object(&mut self, object: &Object) -> Result<()> {
+        let object_path = self.objects_dir.join(object_subpath(object.id()));
+
+        let dir = object_path.parent().unwrap();
+        if !dir.is_dir() {
+            if dir.exists() {
+                return Err(Error::ObjectStoreCorrupt(dir.to_path_buf()));
+            }
+            fs::create_dir(dir)?;
+        }
 
-        object_path.push(path);
         write_object_to_path(object, object_path.as_ref())
     }
 }
 
+fn is_git_dir(path: &Path) -> bool {
+    path.join("HEAD").is_file() && path.join("objects").is_dir() && path.join("refs").is_dir()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match fs::metadata(path) {
+        Ok(m) => m.is_file() && m.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+// A relative `core.objectDirectory` is resolved against the git dir.
+fn configured_objects_dir(git_dir: &Path, config: &Config) -> PathBuf {
+    match config.value("core.objectDirectory") {
+        Some(dir) if !dir.is_empty() => git_dir.join(dir),
+        _ => git_dir.join("objects"),
+    }
+}
+
 // --- init helpers ---
 
+// The `init` helpers below leave any file already copied from a
+// template in place, except that the `core` settings are always set.
+
 fn create_config(git_dir: &Path) -> Result<()> {
     let config_path = git_dir.join("config");
-    let config_txt = "[core]\n\trepositoryformatversion = 0\n\tfilemode = true\n\tbare = false\n\tlogallrefupdates = true\n";
+    let config_txt = match fs::read_to_string(&config_path) {
+        Ok(config_txt) => config_txt,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let config_txt = config::set_values(
+        &config_txt,
+        "core",
+        &[
+            ("repositoryformatversion", "0"),
+            ("filemode", "true"),
+            ("bare", "false"),
+            ("logallrefupdates", "true"),
+        ],
+    );
 
     fs::write(config_path, config_txt).map_err(|e| e.into())
 }
@@ -114,14 +444,14 @@ fn create_description(git_dir: &Path) -> Result<()> {
     let desc_path = git_dir.join("description");
     let desc_txt = "Unnamed repository; edit this file 'description' to name the repository.\n";
 
-    fs::write(desc_path, desc_txt).map_err(|e| e.into())
+    write_if_missing(&desc_path, desc_txt)
 }
 
 fn create_head(git_dir: &Path) -> Result<()> {
     let head_path = git_dir.join("HEAD");
     let head_txt = "ref: refs/heads/master\n";
 
-    fs::write(head_path, head_txt).map_err(|e| e.into())
+    write_if_missing(&head_path, head_txt)
 }
 
 fn create_hooks_dir(git_dir: &Path) -> Result<()> {
@@ -138,14 +468,40 @@ fn create_info_dir(git_dir: &Path) -> Result<()> {
     let exclude_path = info_dir.join("exclude");
     let exclude_txt = "# git ls-files --others --exclude-from=.git/info/exclude\n# Lines that start with '#' are comments.\n# For a project mostly in C, the following would be a good set of\n# exclude patterns (uncomment them if you want to use them):\n# *.[oa]\n# *~\n.DS_Store\n";
 
-    fs::write(exclude_path, exclude_txt).map_err(|e| e.into())
+    write_if_missing(&exclude_path, exclude_txt)
 }
 
-fn create_objects_dir(git_dir: &Path) -> Result<()> {
-    let info_dir = git_dir.join("objects/info");
+fn write_if_missing(path: &Path, content: &str) -> Result<()> {
+    if path.exists() {
+        Ok(())
+    } else {
+        fs::write(path, content).map_err(|e| e.into())
+    }
+}
+
+fn copy_template(from_dir: &Path, to_dir: &Path) -> Result<()> {
+    fs::create_dir_all(to_dir)?;
+
+    for entry in fs::read_dir(from_dir)? {
+        let entry = entry?;
+        let from_path = entry.path();
+        let to_path = to_dir.join(entry.file_name());
+
+        if from_path.is_dir() {
+            copy_template(&from_path, &to_path)?;
+        } else {
+            fs::copy(&from_path, &to_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn create_objects_dir(objects_dir: &Path) -> Result<()> {
+    let info_dir = objects_dir.join("info");
     fs::create_dir_all(&info_dir)?;
 
-    let pack_dir = git_dir.join("objects/pack");
+    let pack_dir = objects_dir.join("pack");
     fs::create_dir_all(&pack_dir).map_err(|e| e.into())
 }
 
@@ -157,19 +513,49 @@ fn create_refs_dir(git_dir: &Path) -> Result<()> {
     fs::create_dir_all(&tags_dir).map_err(|e| e.into())
 }
 
-// --- put_loose_object helpers ---
+// --- loose object helpers ---
+
+// Command-line git always stores loose objects in subdirectories named
+// for the first two hex digits of the object ID.
+const FANOUT_LEN: usize = 2;
+
+// The path of a loose object relative to the objects directory
+// (e.g. `d6/70460b4b4aece5915caf5c68d12f560a9fe3e4`). All reads and writes
+// of loose objects go through this so that they agree on the layout.
+fn object_subpath(id: &Id) -> PathBuf {
+    let object_id = id.to_string();
+    let (dir, file) = object_id.split_at(FANOUT_LEN);
+    [dir, file].iter().collect()
+}
 
 fn write_object_to_path(object: &Object, path: &Path) -> Result<()> {
     let file = OpenOptions::new().write(true).create_new(true).open(path)?;
     let mut z = ZlibEncoder::new(file, Compression::new(1));
 
-    let header = format!("{} {}\0", object.kind(), object.len()).into_bytes();
-    z.write_all(&header)?;
+    z.write_all(&object.header())?;
 
+    // Write directly from the content source's own buffer rather than
+    // copying through another fixed-size buffer.
     let mut object_reader = object.open()?;
-    io::copy(&mut object_reader, &mut z)?;
+    loop {
+        let buf = object_reader.fill_buf()?;
+        let n = buf.len();
+        if n == 0 {
+            break;
+        }
+
+        z.write_all(buf)?;
+        object_reader.consume(n);
+    }
+
+    let file = z.finish()?;
+
+    // Like command-line git, we make loose objects read-only
+    // since their content must never change.
+    let mut permissions = file.metadata()?.permissions();
+    permissions.set_readonly(true);
+    file.set_permissions(permissions)?;
 
-    z.finish()?;
     Ok(())
 }
 
@@ -0,0 +1,243 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use rsgit_core::{
+    object::Id,
+    refs::{validate_ref_name, HeadState, HeadTarget},
+    repo::{Error, Result},
+};
+
+// Command-line git gives up after following this many symbolic refs.
+const MAX_SYMREF_DEPTH: usize = 5;
+
+// The content of a loose ref file: either an object ID or
+// (for a symbolic ref) the name of another ref.
+#[derive(Debug, Eq, PartialEq)]
+enum RefValue {
+    Direct(Id),
+    Symbolic(String),
+}
+
+pub(crate) fn head_state(git_dir: &Path) -> Result<HeadState> {
+    match read_loose_ref(git_dir, "HEAD")? {
+        Some(RefValue::Direct(id)) => Ok(HeadState::Detached(id)),
+        Some(RefValue::Symbolic(name)) => match resolve_ref(git_dir, &name)? {
+            Some(id) => Ok(HeadState::Branch { name, id }),
+            None => Ok(HeadState::Unborn { name }),
+        },
+        None => Err(Error::InvalidRef("HEAD".to_string())),
+    }
+}
+
+pub(crate) fn set_head(git_dir: &Path, target: HeadTarget) -> Result<()> {
+    let content = match target {
+        HeadTarget::Ref(name) => {
+            if !is_valid_symref_target(&name) {
+                return Err(Error::InvalidRef(name));
+            }
+            format!("ref: {}\n", name)
+        }
+        HeadTarget::Detached(id) => format!("{}\n", id),
+    };
+
+    write_loose_ref(git_dir, "HEAD", &content)
+}
+
+// Returns `true` if there are any refs under `refs/`, either as loose
+// ref files or as entries in `packed-refs`.
+pub(crate) fn has_any_refs(git_dir: &Path) -> Result<bool> {
+    if dir_has_files(&git_dir.join("refs"))? {
+        return Ok(true);
+    }
+
+    match fs::read_to_string(git_dir.join("packed-refs")) {
+        Ok(content) => Ok(content
+            .lines()
+            .any(|line| !line.starts_with('#') && !line.starts_with('^'))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn dir_has_files(dir: &Path) -> Result<bool> {
+    if !dir.is_dir() {
+        return Ok(false);
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() || dir_has_files(&path)? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+// Follow a ref (and any symbolic refs it names) to an object ID.
+//
+// Returns `Ok(None)` if the ref doesn't exist.
+pub(crate) fn resolve_ref(git_dir: &Path, name: &str) -> Result<Option<Id>> {
+    let mut name = name.to_string();
+
+    for _ in 0..MAX_SYMREF_DEPTH {
+        let value = match read_loose_ref(git_dir, &name)? {
+            Some(value) => value,
+            None => return read_packed_ref(git_dir, &name),
+        };
+
+        match value {
+            RefValue::Direct(id) => return Ok(Some(id)),
+            RefValue::Symbolic(target) => name = target,
+        }
+    }
+
+    Err(Error::InvalidRef(name))
+}
+
+fn read_loose_ref(git_dir: &Path, name: &str) -> Result<Option<RefValue>> {
+    let path = git_dir.join(name);
+    if path.is_dir() {
+        return Ok(None);
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    match parse_ref_value(content.trim_end()) {
+        Some(value) => Ok(Some(value)),
+        None => Err(Error::InvalidRef(name.to_string())),
+    }
+}
+
+// Like command-line git, we write the new value to `<name>.lock` and then
+// rename it into place so readers never see a partially-written ref.
+fn write_loose_ref(git_dir: &Path, name: &str, content: &str) -> Result<()> {
+    let path = git_dir.join(name);
+    let lock_path = git_dir.join(format!("{}.lock", name));
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&lock_path)?;
+
+    let mut lock = LockFile {
+        path: lock_path,
+        committed: false,
+    };
+
+    file.write_all(content.as_bytes())?;
+    drop(file);
+
+    fs::rename(&lock.path, &path)?;
+    lock.committed = true;
+
+    Ok(())
+}
+
+// Removes a `.lock` file when dropped, unless it has been renamed into
+// place. Otherwise a failed write would block all later writes to the
+// same ref.
+struct LockFile {
+    path: PathBuf,
+    committed: bool,
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            fs::remove_file(&self.path).unwrap_or(());
+        }
+    }
+}
+
+fn is_valid_symref_target(name: &str) -> bool {
+    name.starts_with("refs/") && validate_ref_name(name.as_bytes(), false).is_ok()
+}
+
+fn parse_ref_value(content: &str) -> Option<RefValue> {
+    if let Some(target) = content.strip_prefix("ref:") {
+        let target = target.trim_start();
+        if is_valid_symref_target(target) {
+            Some(RefValue::Symbolic(target.to_string()))
+        } else {
+            None
+        }
+    } else {
+        Id::from_hex(content).ok().map(RefValue::Direct)
+    }
+}
+
+fn read_packed_ref(git_dir: &Path, name: &str) -> Result<Option<Id>> {
+    let content = match fs::read_to_string(git_dir.join("packed-refs")) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(parse_packed_ref(&content, name))
+}
+
+fn parse_packed_ref(content: &str, name: &str) -> Option<Id> {
+    // Lines beginning with `#` are headers; lines beginning with `^`
+    // give the peeled value of the preceding (annotated tag) ref.
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with('^'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ' ');
+            match (parts.next(), parts.next()) {
+                (Some(id), Some(ref_name)) if ref_name == name => Id::from_hex(id).ok(),
+                _ => None,
+            }
+        })
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "d670460b4b4aece5915caf5c68d12f560a9fe3e4";
+
+    #[test]
+    fn parse_ref_value_fn() {
+        assert_eq!(
+            parse_ref_value(ID).unwrap(),
+            RefValue::Direct(Id::from_hex(ID).unwrap())
+        );
+        assert_eq!(
+            parse_ref_value("ref: refs/heads/master").unwrap(),
+            RefValue::Symbolic("refs/heads/master".to_string())
+        );
+
+        assert!(parse_ref_value("").is_none());
+        assert!(parse_ref_value("d670460b").is_none());
+        assert!(parse_ref_value("ref: master").is_none());
+        assert!(parse_ref_value("ref: refs/heads/x..y").is_none());
+    }
+
+    #[test]
+    fn parse_packed_ref_fn() {
+        let content = format!(
+            "# pack-refs with: peeled fully-peeled sorted \n{} refs/heads/master\n{} refs/tags/v1\n^{}\n",
+            ID, "3cd9329ac53613a0bfa198ae28f3af957e49573c", ID
+        );
+
+        assert_eq!(
+            parse_packed_ref(&content, "refs/heads/master").unwrap(),
+            Id::from_hex(ID).unwrap()
+        );
+        assert_eq!(
+            parse_packed_ref(&content, "refs/tags/v1").unwrap(),
+            Id::from_hex("3cd9329ac53613a0bfa198ae28f3af957e49573c").unwrap()
+        );
+        assert!(parse_packed_ref(&content, "refs/heads/other").is_none());
+    }
+}
@@ -0,0 +1,36 @@
+use std::fs;
+
+use super::super::*;
+
+use crate::TempGitRepo;
+
+#[test]
+fn after_init() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let r = OnDiskRepo::init(temp_dir.path()).unwrap();
+
+    assert_eq!(r.config_value("core.bare"), Some("false"));
+    assert_eq!(r.config_value("core.repositoryFormatVersion"), Some("0"));
+    assert_eq!(r.config_value("core.bogus"), None);
+}
+
+#[test]
+fn cached_until_reload() {
+    let mut tgr = TempGitRepo::new();
+    let mut r = OnDiskRepo::open(tgr.path()).unwrap();
+    assert_eq!(r.config_value("user.name"), None);
+
+    tgr.git_command(&["config", "user.name", "A U Thor"]);
+    assert_eq!(r.config_value("user.name"), None);
+
+    r.reload_config().unwrap();
+    assert_eq!(r.config_value("user.name"), Some("A U Thor"));
+
+    // Removing the file doesn't affect the cached values ...
+    fs::remove_file(tgr.path().join(".git/config")).unwrap();
+    assert_eq!(r.config_value("user.name"), Some("A U Thor"));
+
+    // ... until reloaded.
+    r.reload_config().unwrap();
+    assert_eq!(r.config_value("user.name"), None);
+}
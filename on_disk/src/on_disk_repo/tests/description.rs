@@ -0,0 +1,41 @@
+use super::super::*;
+
+use crate::TempGitRepo;
+
+#[test]
+fn fresh_repo() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let r = OnDiskRepo::init(temp_dir.path()).unwrap();
+    assert_eq!(
+        r.description().unwrap(),
+        "Unnamed repository; edit this file 'description' to name the repository."
+    );
+}
+
+#[test]
+fn set_and_read() {
+    let tgr = TempGitRepo::new();
+    let mut r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    r.set_description("My project").unwrap();
+    assert_eq!(r.description().unwrap(), "My project");
+
+    let desc_txt = fs::read_to_string(tgr.path().join(".git/description")).unwrap();
+    assert_eq!(desc_txt, "My project\n");
+
+    r.set_description("Another\n").unwrap();
+    assert_eq!(r.description().unwrap(), "Another");
+}
+
+#[test]
+fn missing_file() {
+    let tgr = TempGitRepo::new();
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    let desc_path = tgr.path().join(".git/description");
+    if desc_path.exists() {
+        fs::remove_file(desc_path).unwrap();
+    }
+
+    assert_eq!(r.description().unwrap(), "");
+}
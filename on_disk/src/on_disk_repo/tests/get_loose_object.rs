@@ -0,0 +1,196 @@
+use std::{fs, io::Write};
+
+use super::{super::*, git_output, git_stdout};
+
+use crate::TempGitRepo;
+
+use flate2::{write::ZlibEncoder, Compression};
+
+use rsgit_core::{
+    object::{ContentLengthMismatch, Id, Kind},
+    repo::Error,
+};
+
+use tempfile::tempdir;
+
+fn read_content(o: &Object) -> Vec<u8> {
+    let mut content = Vec::new();
+    o.open().unwrap().read_to_end(&mut content).unwrap();
+    content
+}
+
+#[test]
+fn all_kinds_match_command_line_git() {
+    let (mut tgr, commit_ids) = TempGitRepo::builder()
+        .commit("initial commit", &[("a.txt", "test content\n")])
+        .annotated_tag("v1", "annotated tag")
+        .build();
+
+    let ids = git_output(&mut tgr, &["rev-parse", "HEAD:a.txt", "HEAD^{tree}", "v1"]);
+    let ids: Vec<Id> = ids.lines().map(|id| Id::from_hex(id).unwrap()).collect();
+
+    let expected = [
+        (&ids[0], Kind::Blob),
+        (&ids[1], Kind::Tree),
+        (&commit_ids[0], Kind::Commit),
+        (&ids[2], Kind::Tag),
+    ];
+
+    let r = OnDiskRepo::new(tgr.path()).unwrap();
+
+    for (id, kind) in &expected {
+        let expected_content =
+            git_stdout(&mut tgr, &["cat-file", &kind.to_string(), &id.to_string()]);
+
+        let o = r.get_loose_object(id).unwrap();
+        assert_eq!(o.id(), *id);
+        assert_eq!(o.kind(), kind);
+        assert_eq!(o.len(), expected_content.len() as u64);
+        assert_eq!(read_content(&o), expected_content);
+
+        // Content can be read more than once.
+        assert_eq!(read_content(&o), expected_content);
+    }
+}
+
+#[test]
+fn round_trip_with_put_loose_object() {
+    let dir = tempdir().unwrap();
+    let mut r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let content = "foobar".repeat(1000).into_bytes();
+    let o = Object::new(&Kind::Blob, Box::new(content.clone())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    let o2 = r.get_loose_object(o.id()).unwrap();
+    assert_eq!(o2.id(), o.id());
+    assert_eq!(o2.kind(), &Kind::Blob);
+    assert_eq!(o2.len(), 6000);
+    assert_eq!(read_content(&o2), content);
+}
+
+#[test]
+fn write_and_read_use_same_path() {
+    let dir = tempdir().unwrap();
+    let mut r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let o = Object::new(&Kind::Blob, Box::new(b"test content\n".to_vec())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    let subpath = object_subpath(o.id());
+    assert_eq!(
+        subpath,
+        Path::new("d6").join("70460b4b4aece5915caf5c68d12f560a9fe3e4")
+    );
+    assert!(r.objects_dir().join(&subpath).is_file());
+
+    let o2 = r.get_loose_object(o.id()).unwrap();
+    assert_eq!(o2.id(), o.id());
+    assert_eq!(read_content(&o2), b"test content\n");
+}
+
+#[test]
+fn error_not_found() {
+    let dir = tempdir().unwrap();
+    let r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let id = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+
+    match r.get_loose_object(&id) {
+        Err(Error::ObjectNotFound(not_found_id)) => assert_eq!(not_found_id, id),
+        Err(err) => panic!("Unexpected error {:?}", err),
+        Ok(_) => panic!("get_loose_object should have failed"),
+    };
+}
+
+#[test]
+fn error_length_mismatch() {
+    let dir = tempdir().unwrap();
+    let r = OnDiskRepo::init(dir.path()).unwrap();
+
+    // Header claims 20 bytes, but only 13 follow.
+    let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+    e.write_all(b"blob 20\0test content\n").unwrap();
+    let compressed = e.finish().unwrap();
+
+    let object_dir = dir.path().join(".git/objects/d6");
+    fs::create_dir_all(&object_dir).unwrap();
+    fs::write(
+        object_dir.join("70460b4b4aece5915caf5c68d12f560a9fe3e4"),
+        compressed,
+    )
+    .unwrap();
+
+    let id = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+
+    match r.get_loose_object(&id) {
+        Err(Error::ObjectLengthMismatch(mismatch_id, mismatch)) => {
+            assert_eq!(mismatch_id, id);
+            assert_eq!(
+                mismatch,
+                ContentLengthMismatch {
+                    expected: 20,
+                    actual: 13
+                }
+            );
+        }
+        Err(err) => panic!("Unexpected error {:?}", err),
+        Ok(_) => panic!("get_loose_object should have failed"),
+    };
+}
+
+#[test]
+fn error_id_mismatch() {
+    let dir = tempdir().unwrap();
+    let mut r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let o = Object::new(&Kind::Blob, Box::new(b"test content\n".to_vec())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    // Move the valid object file to a path belonging to a different ID.
+    let objects_dir = dir.path().join(".git/objects");
+    let wrong_dir = objects_dir.join("e6");
+    fs::create_dir_all(&wrong_dir).unwrap();
+    fs::rename(
+        objects_dir.join("d6/70460b4b4aece5915caf5c68d12f560a9fe3e4"),
+        wrong_dir.join("9de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+    )
+    .unwrap();
+
+    let id = Id::from_hex("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").unwrap();
+
+    match r.get_loose_object(&id) {
+        Err(Error::ObjectIdMismatch(expected, actual)) => {
+            assert_eq!(expected, id);
+            assert_eq!(&actual, o.id());
+        }
+        Err(err) => panic!("Unexpected error {:?}", err),
+        Ok(_) => panic!("get_loose_object should have failed"),
+    };
+}
+
+#[test]
+fn error_malformed_header() {
+    let dir = tempdir().unwrap();
+    let r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+    e.write_all(b"blob thirteen\0test content\n").unwrap();
+    let compressed = e.finish().unwrap();
+
+    let object_dir = dir.path().join(".git/objects/d6");
+    fs::create_dir_all(&object_dir).unwrap();
+    fs::write(
+        object_dir.join("70460b4b4aece5915caf5c68d12f560a9fe3e4"),
+        compressed,
+    )
+    .unwrap();
+
+    let id = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+
+    match r.get_loose_object(&id) {
+        Err(Error::IoError(err)) => assert_eq!(err.kind(), std::io::ErrorKind::InvalidData),
+        Err(err) => panic!("Unexpected error {:?}", err),
+        Ok(_) => panic!("get_loose_object should have failed"),
+    };
+}
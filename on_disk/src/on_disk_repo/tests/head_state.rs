@@ -0,0 +1,213 @@
+use std::fs;
+
+use super::{super::*, git_output};
+
+use crate::TempGitRepo;
+
+fn commit(tgr: &mut TempGitRepo, message: &str) -> Id {
+    tgr.git_command(&[
+        "-c",
+        "user.name=A U Thor",
+        "-c",
+        "user.email=author@example.com",
+        "commit",
+        "--allow-empty",
+        "-m",
+        message,
+    ]);
+
+    let id = git_output(tgr, &["rev-parse", "HEAD"]);
+    Id::from_hex(id).unwrap()
+}
+
+#[test]
+fn unborn() {
+    let mut tgr = TempGitRepo::new();
+    let branch = git_output(&mut tgr, &["symbolic-ref", "HEAD"]);
+
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+    assert_eq!(r.head_state().unwrap(), HeadState::Unborn { name: branch });
+}
+
+#[test]
+fn unborn_rsgit_init() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let r = OnDiskRepo::init(temp_dir.path()).unwrap();
+
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Unborn {
+            name: "refs/heads/master".to_string()
+        }
+    );
+}
+
+#[test]
+fn branch() {
+    let mut tgr = TempGitRepo::new();
+    let id = commit(&mut tgr, "first");
+    let branch = git_output(&mut tgr, &["symbolic-ref", "HEAD"]);
+
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Branch { name: branch, id }
+    );
+
+    tgr.git_command(&["checkout", "-b", "topic"]);
+    let id = commit(&mut tgr, "second");
+
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Branch {
+            name: "refs/heads/topic".to_string(),
+            id
+        }
+    );
+}
+
+#[test]
+fn packed_branch() {
+    let mut tgr = TempGitRepo::new();
+    let id = commit(&mut tgr, "first");
+    let branch = git_output(&mut tgr, &["symbolic-ref", "HEAD"]);
+    tgr.git_command(&["pack-refs", "--all"]);
+
+    assert!(!tgr.path().join(".git").join(&branch).exists());
+
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Branch { name: branch, id }
+    );
+}
+
+#[test]
+fn detached() {
+    let mut tgr = TempGitRepo::new();
+    let first = commit(&mut tgr, "first");
+    commit(&mut tgr, "second");
+
+    tgr.git_command(&["checkout", &first.to_string()]);
+
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+    assert_eq!(r.head_state().unwrap(), HeadState::Detached(first));
+}
+
+#[test]
+fn error_invalid_head() {
+    let tgr = TempGitRepo::new();
+    let head_path = tgr.path().join(".git/HEAD");
+
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    fs::write(&head_path, "sand in the gears\n").unwrap();
+    match r.head_state().unwrap_err() {
+        Error::InvalidRef(name) => assert_eq!(name, "HEAD"),
+        err => panic!("Unexpected error {:?}", err),
+    }
+
+    fs::write(&head_path, "ref: master\n").unwrap();
+    match r.head_state().unwrap_err() {
+        Error::InvalidRef(name) => assert_eq!(name, "HEAD"),
+        err => panic!("Unexpected error {:?}", err),
+    }
+}
+
+#[test]
+fn set_head_round_trip() {
+    let mut tgr = TempGitRepo::new();
+    let first = commit(&mut tgr, "first");
+    let branch = git_output(&mut tgr, &["symbolic-ref", "HEAD"]);
+
+    let mut r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    r.set_head(HeadTarget::Detached(first.clone())).unwrap();
+    assert_eq!(r.head_state().unwrap(), HeadState::Detached(first.clone()));
+    assert_eq!(
+        git_output(&mut tgr, &["rev-parse", "--symbolic-full-name", "HEAD"]),
+        "HEAD"
+    );
+
+    r.set_head(HeadTarget::Ref(branch.clone())).unwrap();
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Branch {
+            name: branch.clone(),
+            id: first
+        }
+    );
+    assert_eq!(git_output(&mut tgr, &["symbolic-ref", "HEAD"]), branch);
+
+    r.set_head(HeadTarget::Ref("refs/heads/topic".to_string()))
+        .unwrap();
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Unborn {
+            name: "refs/heads/topic".to_string()
+        }
+    );
+
+    assert!(!tgr.path().join(".git/HEAD.lock").exists());
+}
+
+#[test]
+fn set_head_removes_lock_on_failure() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut r = OnDiskRepo::init(temp_dir.path()).unwrap();
+
+    // A non-empty directory at HEAD makes the final rename fail.
+    let head_path = temp_dir.path().join(".git/HEAD");
+    fs::remove_file(&head_path).unwrap();
+    fs::create_dir(&head_path).unwrap();
+    fs::write(head_path.join("blocker"), "x").unwrap();
+
+    let target = HeadTarget::Ref("refs/heads/topic".to_string());
+    assert!(r.set_head(target.clone()).is_err());
+    assert!(!temp_dir.path().join(".git/HEAD.lock").exists());
+
+    fs::remove_dir_all(&head_path).unwrap();
+    r.set_head(target).unwrap();
+
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Unborn {
+            name: "refs/heads/topic".to_string()
+        }
+    );
+}
+
+#[test]
+fn set_head_matches_command_line_git() {
+    let mut tgr = TempGitRepo::new();
+    tgr.git_command(&["symbolic-ref", "HEAD", "refs/heads/topic"]);
+    let c_head = fs::read(tgr.path().join(".git/HEAD")).unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut r = OnDiskRepo::init(temp_dir.path()).unwrap();
+    r.set_head(HeadTarget::Ref("refs/heads/topic".to_string()))
+        .unwrap();
+    let r_head = fs::read(temp_dir.path().join(".git/HEAD")).unwrap();
+
+    assert_eq!(r_head, c_head);
+}
+
+#[test]
+fn error_set_head_invalid_ref() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut r = OnDiskRepo::init(temp_dir.path()).unwrap();
+
+    for name in &["master", "refs/heads/x..y", "refs/heads/", "HEAD"] {
+        match r.set_head(HeadTarget::Ref(name.to_string())).unwrap_err() {
+            Error::InvalidRef(err_name) => assert_eq!(&err_name, name),
+            err => panic!("Unexpected error {:?}", err),
+        }
+    }
+
+    assert_eq!(
+        r.head_state().unwrap(),
+        HeadState::Unborn {
+            name: "refs/heads/master".to_string()
+        }
+    );
+}
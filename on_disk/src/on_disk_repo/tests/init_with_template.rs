@@ -0,0 +1,72 @@
+use std::{fs, process::Command};
+
+use super::super::*;
+
+#[test]
+fn copies_template() {
+    let template_dir = tempfile::tempdir().unwrap();
+    let hooks_dir = template_dir.path().join("hooks");
+    fs::create_dir_all(&hooks_dir).unwrap();
+    fs::write(hooks_dir.join("pre-commit"), "#!/bin/sh\nexit 0\n").unwrap();
+    fs::write(template_dir.path().join("description"), "From template\n").unwrap();
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let r = OnDiskRepo::init_with_template(temp_dir.path(), template_dir.path()).unwrap();
+
+    let hook = fs::read_to_string(r.git_dir().join("hooks/pre-commit")).unwrap();
+    assert_eq!(hook, "#!/bin/sh\nexit 0\n");
+
+    // Template files are used in place of the defaults ...
+    assert_eq!(r.description().unwrap(), "From template");
+
+    // ... but other defaults are still created.
+    assert!(r.git_dir().join("HEAD").is_file());
+    assert!(r.git_dir().join("info/exclude").is_file());
+    assert_eq!(r.config_value("core.bare"), Some("false"));
+}
+
+#[test]
+fn template_config_matches_command_line_git() {
+    let configs = [
+        "[user]\n\tname = x\n",
+        "[core]\n\tbare = true\n\tfoo = y\n[user]\n\tname = x\n",
+    ];
+
+    for config_txt in &configs {
+        let template_dir = tempfile::tempdir().unwrap();
+        fs::write(template_dir.path().join("config"), config_txt).unwrap();
+
+        let c_temp = tempfile::tempdir().unwrap();
+        let output = Command::new("git")
+            .arg("init")
+            .arg(format!("--template={}", template_dir.path().display()))
+            .arg(c_temp.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let c_config = fs::read_to_string(c_temp.path().join(".git/config")).unwrap();
+
+        let r_temp = tempfile::tempdir().unwrap();
+        let r = OnDiskRepo::init_with_template(r_temp.path(), template_dir.path()).unwrap();
+        let r_config = fs::read_to_string(r.git_dir().join("config")).unwrap();
+
+        assert_eq!(r_config, c_config);
+        assert_eq!(r.config_value("user.name"), Some("x"));
+        assert_eq!(r.config_value("core.bare"), Some("false"));
+        assert_eq!(r.config_value("core.repositoryformatversion"), Some("0"));
+    }
+}
+
+#[test]
+fn error_template_doesnt_exist() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let template_dir = temp_dir.path().join("no-such-template");
+    let work_dir = temp_dir.path().join("repo");
+
+    match OnDiskRepo::init_with_template(&work_dir, &template_dir).unwrap_err() {
+        Error::TemplateDirDoesntExist(path) => assert_eq!(path, template_dir),
+        err => panic!("Unexpected error {:?}", err),
+    }
+
+    assert!(!work_dir.exists());
+}
@@ -0,0 +1,56 @@
+use std::fs;
+
+use super::super::*;
+
+use crate::TempGitRepo;
+
+#[test]
+fn fresh_repo() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let r = OnDiskRepo::init(temp_dir.path()).unwrap();
+    assert!(r.is_empty().unwrap());
+
+    let tgr = TempGitRepo::new();
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+    assert!(r.is_empty().unwrap());
+}
+
+#[test]
+fn after_commit() {
+    let mut tgr = TempGitRepo::new();
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+    assert!(r.is_empty().unwrap());
+
+    tgr.git_command(&[
+        "-c",
+        "user.name=A U Thor",
+        "-c",
+        "user.email=author@example.com",
+        "commit",
+        "--allow-empty",
+        "-m",
+        "first",
+    ]);
+    assert!(!r.is_empty().unwrap());
+
+    // Still not empty when the only ref is packed ...
+    tgr.git_command(&["pack-refs", "--all"]);
+    assert!(!r.is_empty().unwrap());
+
+    // ... or when HEAD points at an unborn branch but other refs exist.
+    tgr.git_command(&["checkout", "--orphan", "other"]);
+    assert!(!r.is_empty().unwrap());
+}
+
+#[test]
+fn ignores_objects_without_refs() {
+    let tgr = TempGitRepo::new();
+    let mut r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    let o = Object::blob(Box::new(b"test content\n".to_vec())).unwrap();
+    r.write_object(&o).unwrap();
+    assert!(r.is_empty().unwrap());
+
+    fs::create_dir_all(tgr.path().join(".git/refs/heads/nested")).unwrap();
+    assert!(r.is_empty().unwrap());
+}
@@ -1,2 +1,31 @@
+use crate::TempGitRepo;
+
+mod config_value;
+mod description;
+mod get_loose_object;
+mod head_state;
+mod init_with_template;
+mod is_empty;
 mod new;
+mod open;
 mod put_loose_object;
+mod run_hook;
+mod verified_open_loose_object;
+mod write_object;
+
+// Run a git command in `tgr` and return its stdout.
+// Panics if the command fails.
+fn git_stdout(tgr: &mut TempGitRepo, args: &[&str]) -> Vec<u8> {
+    let output = tgr.command("git").args(args).output().unwrap();
+    assert!(output.status.success());
+    output.stdout
+}
+
+// Like `git_stdout`, but returns the output as a string
+// without its trailing newline.
+fn git_output(tgr: &mut TempGitRepo, args: &[&str]) -> String {
+    String::from_utf8(git_stdout(tgr, args))
+        .unwrap()
+        .trim_end()
+        .to_string()
+}
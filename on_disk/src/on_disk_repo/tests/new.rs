@@ -10,8 +10,10 @@ fn happy_path() {
     let work_dir = tgr.path();
     let git_dir = work_dir.join(".git");
     let r = OnDiskRepo::new(&work_dir).unwrap();
-    assert_eq!(r.work_dir(), work_dir);
+    assert_eq!(r.work_dir().unwrap(), work_dir);
+    assert!(!r.is_bare());
     assert_eq!(r.git_dir(), git_dir.as_path());
+    assert_eq!(r.objects_dir(), git_dir.join("objects").as_path());
 }
 
 #[test]
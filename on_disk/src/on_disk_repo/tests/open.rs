@@ -0,0 +1,86 @@
+use std::{fs, process::Command};
+
+use super::super::*;
+
+use crate::TempGitRepo;
+
+#[test]
+fn non_bare_work_dir() {
+    let tgr = TempGitRepo::new();
+    let work_dir = tgr.path();
+    let git_dir = work_dir.join(".git");
+
+    let r = OnDiskRepo::open(&work_dir).unwrap();
+    assert!(!r.is_bare());
+    assert_eq!(r.work_dir().unwrap(), work_dir);
+    assert_eq!(r.git_dir(), git_dir.as_path());
+    assert_eq!(r.objects_dir(), git_dir.join("objects").as_path());
+}
+
+#[test]
+fn non_bare_git_dir() {
+    let tgr = TempGitRepo::new();
+    let work_dir = tgr.path();
+    let git_dir = work_dir.join(".git");
+
+    let r = OnDiskRepo::open(&git_dir).unwrap();
+    assert!(!r.is_bare());
+    assert_eq!(r.work_dir().unwrap(), work_dir);
+    assert_eq!(r.git_dir(), git_dir.as_path());
+}
+
+#[test]
+fn bare() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let git_dir = temp_dir.path().join("bare.git");
+
+    let output = Command::new("git")
+        .args(&["init", "--bare", git_dir.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let r = OnDiskRepo::open(&git_dir).unwrap();
+    assert!(r.is_bare());
+    assert!(r.work_dir().is_none());
+    assert_eq!(r.git_dir(), git_dir.as_path());
+    assert_eq!(r.objects_dir(), git_dir.join("objects").as_path());
+}
+
+#[test]
+fn error_no_path() {
+    let tgr = TempGitRepo::new();
+    let path = tgr.path().join("bogus");
+    let err = OnDiskRepo::open(&path).unwrap_err();
+    if let Error::WorkDirDoesntExist(_) = err {
+        // expected
+    } else {
+        panic!("wrong error: {:?}", err);
+    }
+}
+
+#[test]
+fn error_not_a_repo() {
+    let tempdir = tempfile::tempdir().unwrap();
+    let err = OnDiskRepo::open(tempdir.path()).unwrap_err();
+    if let Error::GitDirDoesntExist(_) = err {
+        // expected
+    } else {
+        panic!("wrong error: {:?}", err);
+    }
+}
+
+#[test]
+fn error_invalid_bare_value() {
+    let tgr = TempGitRepo::new();
+    let git_dir = tgr.path().join(".git");
+    fs::write(git_dir.join("config"), "[core]\n\tbare = maybe\n").unwrap();
+
+    let err = OnDiskRepo::open(&git_dir).unwrap_err();
+    if let Error::InvalidConfigValue(name, value) = err {
+        assert_eq!(name, "core.bare");
+        assert_eq!(value, "maybe");
+    } else {
+        panic!("wrong error: {:?}", err);
+    }
+}
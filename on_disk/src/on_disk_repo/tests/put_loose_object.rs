@@ -1,9 +1,11 @@
-use std::io::Write;
+use std::io::{Read, Write};
 
 use super::super::*;
 
 use crate::TempGitRepo;
 
+use flate2::read::ZlibDecoder;
+
 use rsgit_core::object::{Kind, Object};
 
 use tempfile::{tempdir, NamedTempFile};
@@ -65,6 +67,98 @@ fn matches_command_line_git_large_file() {
     assert!(!dir_diff::is_different(tgr.path(), r_path).unwrap());
 }
 
+#[cfg(unix)]
+#[test]
+fn object_is_read_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut test_file = NamedTempFile::new().unwrap();
+    test_file.write_all(TEST_CONTENT).unwrap();
+
+    let mut tgr = TempGitRepo::new();
+    tgr.git_command(&["hash-object", "-w", test_file.path().to_str().unwrap()]);
+
+    let object_subpath = ".git/objects/d6/70460b4b4aece5915caf5c68d12f560a9fe3e4";
+    let c_mode = fs::metadata(tgr.path().join(object_subpath))
+        .unwrap()
+        .permissions()
+        .mode();
+
+    let rsgit_temp = tempdir().unwrap();
+    let r_path = rsgit_temp.path();
+    let mut r = OnDiskRepo::init(r_path).unwrap();
+
+    let o = Object::new(&Kind::Blob, Box::new(TEST_CONTENT.to_vec())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    let r_mode = fs::metadata(r_path.join(object_subpath))
+        .unwrap()
+        .permissions()
+        .mode();
+
+    assert_eq!(r_mode & 0o777, c_mode & 0o777);
+    assert_eq!(r_mode & 0o222, 0);
+}
+
+#[test]
+fn overridden_objects_dir() {
+    let rsgit_temp = tempdir().unwrap();
+    let r_path = rsgit_temp.path();
+    let mut r = OnDiskRepo::init(r_path).unwrap();
+
+    let objects_temp = tempdir().unwrap();
+    let objects_dir = objects_temp.path();
+    r.set_objects_dir(objects_dir);
+    assert_eq!(r.objects_dir(), objects_dir);
+
+    let o = Object::new(&Kind::Blob, Box::new(TEST_CONTENT.to_vec())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    let default_path = r_path.join(".git/objects/d6/70460b4b4aece5915caf5c68d12f560a9fe3e4");
+    assert!(!default_path.exists());
+
+    let object_path = objects_dir.join("d6/70460b4b4aece5915caf5c68d12f560a9fe3e4");
+    let file = fs::File::open(&object_path).unwrap();
+
+    let mut content = Vec::new();
+    ZlibDecoder::new(file).read_to_end(&mut content).unwrap();
+    assert_eq!(content.as_slice(), b"blob 13\0test content\n");
+
+    let o2 = r.get_loose_object(o.id()).unwrap();
+    assert_eq!(o2.id(), o.id());
+}
+
+#[test]
+fn configured_objects_dir() {
+    let rsgit_temp = tempdir().unwrap();
+    let r_path = rsgit_temp.path();
+    OnDiskRepo::init(r_path).unwrap();
+
+    let config_path = r_path.join(".git/config");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("\tobjectDirectory = elsewhere\n");
+    fs::write(&config_path, config).unwrap();
+
+    let objects_dir = r_path.join(".git/elsewhere");
+    fs::create_dir(&objects_dir).unwrap();
+
+    let mut r = OnDiskRepo::new(r_path).unwrap();
+    assert_eq!(r.objects_dir(), objects_dir.as_path());
+
+    let o = Object::new(&Kind::Blob, Box::new(TEST_CONTENT.to_vec())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    assert!(objects_dir
+        .join("d6/70460b4b4aece5915caf5c68d12f560a9fe3e4")
+        .is_file());
+
+    let r = OnDiskRepo::open(r_path).unwrap();
+    assert_eq!(r.objects_dir(), objects_dir.as_path());
+
+    let o2 = r.get_loose_object(o.id()).unwrap();
+    assert_eq!(o2.id(), o.id());
+}
+
 #[test]
 fn error_cant_create_objects_dir() {
     let rsgit_temp = tempdir().unwrap();
@@ -78,7 +172,7 @@ fn error_cant_create_objects_dir() {
     let err = r.put_loose_object(&o).unwrap_err();
 
     match err {
-        Error::IoError(err) => assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists),
+        Error::ObjectStoreCorrupt(path) => assert_eq!(path, objects_dir),
         _ => panic!("Unexpected error {:?}", err),
     }
 }
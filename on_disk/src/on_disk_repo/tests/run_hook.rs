@@ -0,0 +1,67 @@
+use super::super::*;
+
+use crate::TempGitRepo;
+
+#[test]
+fn no_hook() {
+    let tgr = TempGitRepo::new();
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    assert!(r.run_hook("post-commit", &[], b"").unwrap().is_none());
+}
+
+#[cfg(unix)]
+fn write_hook(r: &OnDiskRepo, name: &str, script: &str, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let hook_path = r.git_dir().join("hooks").join(name);
+    fs::write(&hook_path, script).unwrap();
+    fs::set_permissions(&hook_path, fs::Permissions::from_mode(mode)).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn post_commit() {
+    let tgr = TempGitRepo::new();
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    write_hook(
+        &r,
+        "post-commit",
+        "#!/bin/sh\necho \"$1\" > marker\ncat >> marker\n",
+        0o755,
+    );
+
+    let status = r
+        .run_hook("post-commit", &["arg1"], b"from stdin\n")
+        .unwrap()
+        .unwrap();
+    assert!(status.success());
+
+    let marker = fs::read_to_string(tgr.path().join("marker")).unwrap();
+    assert_eq!(marker, "arg1\nfrom stdin\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn exit_status() {
+    let tgr = TempGitRepo::new();
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    // The hook exits without reading the (large) input.
+    write_hook(&r, "pre-commit", "#!/bin/sh\nexit 3\n", 0o755);
+
+    let stdin = vec![b'x'; 1024 * 1024];
+    let status = r.run_hook("pre-commit", &[], &stdin).unwrap().unwrap();
+    assert_eq!(status.code(), Some(3));
+}
+
+#[cfg(unix)]
+#[test]
+fn not_executable() {
+    let tgr = TempGitRepo::new();
+    let r = OnDiskRepo::open(tgr.path()).unwrap();
+
+    write_hook(&r, "post-commit", "#!/bin/sh\nexit 0\n", 0o644);
+    assert!(r.run_hook("post-commit", &[], b"").unwrap().is_none());
+}
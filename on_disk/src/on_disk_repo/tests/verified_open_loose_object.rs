@@ -0,0 +1,117 @@
+use std::{
+    fs,
+    io::{ErrorKind, Read, Write},
+};
+
+use super::super::*;
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+
+use rsgit_core::object::{Id, Kind};
+
+use tempfile::tempdir;
+
+// Large enough to span many reads through the buffered reader.
+fn large_content() -> Vec<u8> {
+    (0..200_000u32).map(|n| (n % 251) as u8).collect()
+}
+
+// Rewrite a loose object file, replacing its inflated bytes
+// (header and content) with the result of `f`.
+fn rewrite_object(r: &OnDiskRepo, id: &Id, f: impl FnOnce(&mut Vec<u8>)) {
+    let path = r.objects_dir().join(object_subpath(id));
+
+    let mut inflated = Vec::new();
+    ZlibDecoder::new(fs::File::open(&path).unwrap())
+        .read_to_end(&mut inflated)
+        .unwrap();
+
+    f(&mut inflated);
+
+    let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+    e.write_all(&inflated).unwrap();
+
+    // put_loose_object leaves the file read-only.
+    fs::remove_file(&path).unwrap();
+    fs::write(&path, e.finish().unwrap()).unwrap();
+}
+
+#[test]
+fn reads_valid_object() {
+    let dir = tempdir().unwrap();
+    let mut r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let content = large_content();
+    let o = Object::new(&Kind::Blob, Box::new(content.clone())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    let mut read_content = Vec::new();
+    r.verified_open_loose_object(o.id())
+        .unwrap()
+        .read_to_end(&mut read_content)
+        .unwrap();
+
+    assert_eq!(read_content, content);
+}
+
+#[test]
+fn error_corrupt_byte_at_stream_end() {
+    let dir = tempdir().unwrap();
+    let mut r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let content = large_content();
+    let o = Object::new(&Kind::Blob, Box::new(content.clone())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    rewrite_object(&r, o.id(), |inflated| {
+        let n = inflated.len() / 2;
+        inflated[n] ^= 0x01;
+    });
+
+    let mut reader = r.verified_open_loose_object(o.id()).unwrap();
+
+    // Everything but the end of the stream reads without error ...
+    let mut buf = vec![0u8; content.len()];
+    reader.read_exact(&mut buf).unwrap();
+    assert_ne!(buf, content);
+
+    // ... and the mismatch is reported when EOF is reached.
+    let err = reader.read(&mut [0u8; 16]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("content hashes to"));
+}
+
+#[test]
+fn error_length_mismatch_at_stream_end() {
+    let dir = tempdir().unwrap();
+    let mut r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let o = Object::new(&Kind::Blob, Box::new(b"test content\n".to_vec())).unwrap();
+    r.put_loose_object(&o).unwrap();
+
+    rewrite_object(&r, o.id(), |inflated| inflated.truncate(inflated.len() - 1));
+
+    let mut content = Vec::new();
+    let err = r
+        .verified_open_loose_object(o.id())
+        .unwrap()
+        .read_to_end(&mut content)
+        .unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("header says 13 bytes"));
+}
+
+#[test]
+fn error_not_found() {
+    let dir = tempdir().unwrap();
+    let r = OnDiskRepo::init(dir.path()).unwrap();
+
+    let id = Id::from_hex("d670460b4b4aece5915caf5c68d12f560a9fe3e4").unwrap();
+
+    match r.verified_open_loose_object(&id) {
+        Err(Error::ObjectNotFound(not_found_id)) => assert_eq!(not_found_id, id),
+        Err(err) => panic!("Unexpected error {:?}", err),
+        Ok(_) => panic!("verified_open_loose_object should have failed"),
+    };
+}
@@ -0,0 +1,70 @@
+use super::super::*;
+
+use rsgit_core::{
+    object::{Kind, Object},
+    repo::WriteOutcome,
+};
+
+use tempfile::tempdir;
+
+const TEST_CONTENT: &[u8; 13] = b"test content\n";
+
+#[test]
+fn returns_id() {
+    let rsgit_temp = tempdir().unwrap();
+    let r_path = rsgit_temp.path();
+    let mut r = OnDiskRepo::init(r_path).unwrap();
+
+    let o = Object::new(&Kind::Blob, Box::new(TEST_CONTENT.to_vec())).unwrap();
+    assert!(!r.has_object(o.id()).unwrap());
+
+    let (id, outcome) = r.write_object(&o).unwrap();
+    assert_eq!(outcome, WriteOutcome::Created);
+    assert_eq!(id.to_string(), "d670460b4b4aece5915caf5c68d12f560a9fe3e4");
+    assert!(r.has_object(&id).unwrap());
+
+    let object_path = r_path.join(".git/objects/d6/70460b4b4aece5915caf5c68d12f560a9fe3e4");
+    assert!(object_path.is_file());
+}
+
+#[test]
+fn duplicate_write() {
+    let rsgit_temp = tempdir().unwrap();
+    let r_path = rsgit_temp.path();
+    let mut r = OnDiskRepo::init(r_path).unwrap();
+
+    let o = Object::new(&Kind::Blob, Box::new(TEST_CONTENT.to_vec())).unwrap();
+    let (id1, outcome1) = r.write_object(&o).unwrap();
+    let (id2, outcome2) = r.write_object(&o).unwrap();
+    assert_eq!(id1, id2);
+    assert_eq!(outcome1, WriteOutcome::Created);
+    assert_eq!(outcome2, WriteOutcome::AlreadyExisted);
+
+    // put_loose_object, by contrast, refuses to overwrite.
+    let err = r.put_loose_object(&o).unwrap_err();
+    match err {
+        Error::IoError(err) => assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists),
+        _ => panic!("Unexpected error {:?}", err),
+    }
+}
+
+#[test]
+fn shared_fan_out_dir() {
+    // Writing a second object whose ID also begins with `d6` must
+    // reuse the existing fan-out directory rather than fail.
+
+    let rsgit_temp = tempdir().unwrap();
+    let r_path = rsgit_temp.path();
+    let mut r = OnDiskRepo::init(r_path).unwrap();
+
+    let o1 = Object::new(&Kind::Blob, Box::new(TEST_CONTENT.to_vec())).unwrap();
+    r.write_object(&o1).unwrap();
+
+    let o2 = (0..)
+        .map(|n| Object::new(&Kind::Blob, Box::new(format!("{}", n).into_bytes())).unwrap())
+        .find(|o| o.id().to_string().starts_with("d6"))
+        .unwrap();
+
+    let (id2, _) = r.write_object(&o2).unwrap();
+    assert!(r.has_object(&id2).unwrap());
+}
@@ -5,6 +5,8 @@ use std::{
     process::Command,
 };
 
+use rsgit_core::object::Id;
+
 /// A `TempGitRepo` creates a temporary, empty repo using
 /// the command-line git from the host system. This is often
 /// used in unit tests to compare output with comparable
@@ -83,6 +85,11 @@ impl TempGitRepo {
         fs::write(git_info_exclude_path, git_info_exclude_txt).unwrap();
     }
 
+    // Create a builder which populates a new repo with commits and tags.
+    pub fn builder() -> TempGitRepoBuilder {
+        TempGitRepoBuilder::default()
+    }
+
     // Return the path for this repo's root (working directory).
     pub fn path(&self) -> &Path {
         self.path.as_path()
@@ -117,6 +124,141 @@ impl TempGitRepo {
     }
 }
 
+/// Builds a [`TempGitRepo`] pre-populated with commits and tags,
+/// for tests that need a repo in a known state.
+///
+/// Like C git's test suite, author and committer identities and
+/// timestamps are fixed (the timestamp advances by one minute per
+/// commit) so that the resulting object IDs are reproducible.
+///
+/// [`TempGitRepo`]: struct.TempGitRepo.html
+#[derive(Default)]
+pub struct TempGitRepoBuilder {
+    steps: Vec<BuildStep>,
+}
+
+enum BuildStep {
+    Commit {
+        message: String,
+        files: Vec<(String, Vec<u8>)>,
+    },
+    Tag(String),
+    AnnotatedTag {
+        name: String,
+        message: String,
+    },
+}
+
+// Matches `test_tick` in C git's test-lib.sh.
+const FIRST_COMMIT_TIME: i64 = 1_112_911_993;
+
+impl TempGitRepoBuilder {
+    // Add a commit with the given message. Each `(path, content)` pair in
+    // `files` is written to the working tree and staged first. A commit
+    // with no files is allowed (it has the same tree as its parent).
+    pub fn commit<P, C>(mut self, message: &str, files: &[(P, C)]) -> TempGitRepoBuilder
+    where
+        P: AsRef<str>,
+        C: AsRef<[u8]>,
+    {
+        self.steps.push(BuildStep::Commit {
+            message: message.to_string(),
+            files: files
+                .iter()
+                .map(|(path, content)| (path.as_ref().to_string(), content.as_ref().to_vec()))
+                .collect(),
+        });
+        self
+    }
+
+    // Add a lightweight tag pointing at the most recent commit.
+    pub fn tag(mut self, name: &str) -> TempGitRepoBuilder {
+        self.steps.push(BuildStep::Tag(name.to_string()));
+        self
+    }
+
+    // Add an annotated tag pointing at the most recent commit. Like commits,
+    // it has a fixed tagger identity and timestamp.
+    pub fn annotated_tag(mut self, name: &str, message: &str) -> TempGitRepoBuilder {
+        self.steps.push(BuildStep::AnnotatedTag {
+            name: name.to_string(),
+            message: message.to_string(),
+        });
+        self
+    }
+
+    // Create the repo and perform each step in order. Returns the repo
+    // and the IDs of the commits that were created, in order.
+    pub fn build(self) -> (TempGitRepo, Vec<Id>) {
+        let mut tgr = TempGitRepo::new();
+        let mut commit_ids = Vec::new();
+        let mut time = FIRST_COMMIT_TIME;
+
+        for step in self.steps {
+            match step {
+                BuildStep::Commit { message, files } => {
+                    for (path, content) in files {
+                        let path = tgr.path().join(path);
+                        if let Some(parent) = path.parent() {
+                            fs::create_dir_all(parent).unwrap();
+                        }
+                        fs::write(path, content).unwrap();
+                    }
+
+                    tgr.git_command(&["add", "--all"]);
+
+                    let date = format!("{} -0700", time);
+                    time += 60;
+
+                    let output = tgr
+                        .command("git")
+                        .env("GIT_AUTHOR_NAME", "A U Thor")
+                        .env("GIT_AUTHOR_EMAIL", "author@example.com")
+                        .env("GIT_AUTHOR_DATE", &date)
+                        .env("GIT_COMMITTER_NAME", "C O Mitter")
+                        .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+                        .env("GIT_COMMITTER_DATE", &date)
+                        .args(&["commit", "--allow-empty", "--quiet", "-m", &message])
+                        .output()
+                        .unwrap();
+                    assert!(output.status.success(), "git commit failed");
+
+                    let output = tgr
+                        .command("git")
+                        .args(&["rev-parse", "HEAD"])
+                        .output()
+                        .unwrap();
+                    assert!(output.status.success(), "git rev-parse failed");
+
+                    let id = std::str::from_utf8(&output.stdout).unwrap().trim_end();
+                    commit_ids.push(Id::from_hex(id).unwrap());
+                }
+
+                BuildStep::Tag(name) => {
+                    tgr.git_command(&["tag", &name]);
+                }
+
+                BuildStep::AnnotatedTag { name, message } => {
+                    let date = format!("{} -0700", time);
+                    time += 60;
+
+                    let output = tgr
+                        .command("git")
+                        .env("GIT_COMMITTER_NAME", "C O Mitter")
+                        .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+                        .env("GIT_COMMITTER_DATE", &date)
+                        .args(&["tag", "-a", "-m", &message, &name])
+                        .output()
+                        .unwrap();
+                    assert!(output.status.success(), "git tag failed");
+                }
+            }
+        }
+
+        (tgr, commit_ids)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::TempGitRepo;
@@ -159,6 +301,54 @@ mod tests {
         assert_eq!(repo_dir.is_dir(), true);
     }
 
+    #[test]
+    fn builder() {
+        use crate::OnDiskRepo;
+        use rsgit_core::{refs::HeadState, repo::Repo};
+
+        let (mut tgr, ids) = TempGitRepo::builder()
+            .commit("first", &[("a.txt", "a\n"), ("dir/b.txt", "b\n")])
+            .tag("v1")
+            .commit("second", &[("a.txt", "changed\n")])
+            .annotated_tag("v2", "second release")
+            .build();
+
+        assert_eq!(ids.len(), 2);
+        assert!(tgr.path().join("dir/b.txt").is_file());
+
+        let r = OnDiskRepo::open(tgr.path()).unwrap();
+        match r.head_state().unwrap() {
+            HeadState::Branch { id, .. } => assert_eq!(id, ids[1]),
+            head => panic!("Unexpected HEAD state {:?}", head),
+        }
+
+        let output = tgr
+            .command("git")
+            .args(&["rev-parse", "v1", "HEAD^"])
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            format!("{}\n{}\n", ids[0], ids[0])
+        );
+
+        let output = tgr
+            .command("git")
+            .args(&["cat-file", "-t", "v2"])
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, b"tag\n");
+
+        // Identities and timestamps are fixed, so IDs are reproducible.
+        let (_tgr2, ids2) = TempGitRepo::builder()
+            .commit("first", &[("a.txt", "a\n"), ("dir/b.txt", "b\n")])
+            .tag("v1")
+            .commit("second", &[("a.txt", "changed\n")])
+            .annotated_tag("v2", "second release")
+            .build();
+        assert_eq!(ids, ids2);
+    }
+
     #[test]
     #[should_panic(expected = "git command failed with status")]
     fn git_command_error() {
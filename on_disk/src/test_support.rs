@@ -0,0 +1,175 @@
+//! Helpers for tests which compare rsgit's results with command-line git.
+//!
+//! Like [`TempGitRepo`], these functions panic instead of returning
+//! `Result` since they are intended for use in tests.
+//!
+//! [`TempGitRepo`]: ../struct.TempGitRepo.html
+
+use std::{collections::BTreeMap, fs, io::Read, path::Path};
+
+use flate2::read::ZlibDecoder;
+
+use crate::OnDiskRepo;
+
+/// Assert that two repositories contain exactly the same set of objects
+/// and that each object's (decompressed) content is identical.
+///
+/// Unlike comparing the `.git` directories byte-for-byte, this doesn't
+/// depend on zlib compression settings or on files unrelated to the
+/// object store.
+///
+/// Only loose objects are compared. Panics if either repository
+/// contains pack files, since rsgit can't read those yet.
+pub fn assert_same_objects(repo_a: &OnDiskRepo, repo_b: &OnDiskRepo) {
+    let objects_a = loose_objects(repo_a.objects_dir());
+    let objects_b = loose_objects(repo_b.objects_dir());
+
+    let only_in_a: Vec<&String> = objects_a
+        .keys()
+        .filter(|id| !objects_b.contains_key(*id))
+        .collect();
+
+    let only_in_b: Vec<&String> = objects_b
+        .keys()
+        .filter(|id| !objects_a.contains_key(*id))
+        .collect();
+
+    assert!(
+        only_in_a.is_empty() && only_in_b.is_empty(),
+        "\nobject sets differ\n\nonly in {}:\n{:#?}\n\nonly in {}:\n{:#?}\n",
+        repo_a.git_dir().display(),
+        only_in_a,
+        repo_b.git_dir().display(),
+        only_in_b
+    );
+
+    for (id, content_a) in objects_a.iter() {
+        assert!(
+            content_a == &objects_b[id],
+            "\ncontent of object {} differs\n",
+            id
+        );
+    }
+}
+
+// Returns a map of object ID to decompressed object (header and content).
+fn loose_objects(objects_dir: &Path) -> BTreeMap<String, Vec<u8>> {
+    let pack_dir = objects_dir.join("pack");
+    if pack_dir.is_dir() {
+        let has_packs = fs::read_dir(&pack_dir)
+            .unwrap()
+            .any(|entry| entry.unwrap().path().extension() == Some("pack".as_ref()));
+        assert!(
+            !has_packs,
+            "packed objects in {} are not supported",
+            pack_dir.display()
+        );
+    }
+
+    let mut objects = BTreeMap::new();
+
+    for entry in fs::read_dir(objects_dir).unwrap() {
+        let entry = entry.unwrap();
+        let dir_name = entry.file_name().to_string_lossy().to_string();
+        if dir_name.len() != 2 || !entry.path().is_dir() {
+            continue;
+        }
+
+        for object in fs::read_dir(entry.path()).unwrap() {
+            let object = object.unwrap();
+            let id = format!("{}{}", dir_name, object.file_name().to_string_lossy());
+
+            let file = fs::File::open(object.path()).unwrap();
+            let mut content = Vec::new();
+            ZlibDecoder::new(file).read_to_end(&mut content).unwrap();
+
+            objects.insert(id, content);
+        }
+    }
+
+    objects
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use super::*;
+
+    use crate::TempGitRepo;
+
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use rsgit_core::{object::Object, repo::Repo};
+
+    #[test]
+    fn same_objects() {
+        let mut c_tgr = TempGitRepo::new();
+        c_tgr.git_command(&["hash-object", "-w", "--stdin"]);
+
+        let r_tgr = TempGitRepo::new();
+        let mut r = OnDiskRepo::open(r_tgr.path()).unwrap();
+        r.write_object(&Object::blob(Box::new(Vec::new())).unwrap())
+            .unwrap();
+
+        let c = OnDiskRepo::open(c_tgr.path()).unwrap();
+        assert_same_objects(&c, &r);
+    }
+
+    #[test]
+    fn ignores_compression_level() {
+        let c_tgr = TempGitRepo::new();
+        let mut c = OnDiskRepo::open(c_tgr.path()).unwrap();
+        c.write_object(&Object::blob(Box::new(b"test content\n".to_vec())).unwrap())
+            .unwrap();
+
+        let r_tgr = TempGitRepo::new();
+        let r = OnDiskRepo::open(r_tgr.path()).unwrap();
+
+        let object_dir = r.objects_dir().join("d6");
+        fs::create_dir(&object_dir).unwrap();
+        let file =
+            fs::File::create(object_dir.join("70460b4b4aece5915caf5c68d12f560a9fe3e4")).unwrap();
+        let mut z = ZlibEncoder::new(file, Compression::best());
+        z.write_all(b"blob 13\0test content\n").unwrap();
+        z.finish().unwrap();
+
+        assert_same_objects(&c, &r);
+    }
+
+    #[test]
+    #[should_panic(expected = "object sets differ")]
+    fn different_objects() {
+        let c_tgr = TempGitRepo::new();
+        let mut c = OnDiskRepo::open(c_tgr.path()).unwrap();
+        c.write_object(&Object::blob(Box::new(b"test content\n".to_vec())).unwrap())
+            .unwrap();
+
+        let r_tgr = TempGitRepo::new();
+        let r = OnDiskRepo::open(r_tgr.path()).unwrap();
+
+        assert_same_objects(&c, &r);
+    }
+
+    #[test]
+    #[should_panic(expected = "content of object d670460b4b4aece5915caf5c68d12f560a9fe3e4 differs")]
+    fn different_content() {
+        let c_tgr = TempGitRepo::new();
+        let mut c = OnDiskRepo::open(c_tgr.path()).unwrap();
+        c.write_object(&Object::blob(Box::new(b"test content\n".to_vec())).unwrap())
+            .unwrap();
+
+        let r_tgr = TempGitRepo::new();
+        let r = OnDiskRepo::open(r_tgr.path()).unwrap();
+
+        let object_dir = r.objects_dir().join("d6");
+        fs::create_dir(&object_dir).unwrap();
+        let file =
+            fs::File::create(object_dir.join("70460b4b4aece5915caf5c68d12f560a9fe3e4")).unwrap();
+        let mut z = ZlibEncoder::new(file, Compression::new(1));
+        z.write_all(b"blob 13\0sand in gears\n").unwrap();
+        z.finish().unwrap();
+
+        assert_same_objects(&c, &r);
+    }
+}